@@ -1,7 +1,7 @@
 use futures_util::StreamExt;
 use tokio_socketcan::{CANFrame, CANSocket};
 
-use col::sdo::SDOServerResponse;
+use col::{sdo::SDOServerResponse, NodeId};
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub enum ValueType {
@@ -14,45 +14,26 @@ pub enum ValueType {
 
 pub async fn write_remote_object(
     can_socket: &mut CANSocket,
-    node: u8,
+    node: NodeId,
     index: u16,
     subindex: u8,
     value_type: ValueType,
     value: u32,
 ) -> () {
     const SDO_RECEIVE: u32 = 0x600;
-    let frame: CANFrame = match value_type {
-        ValueType::U8 => {
-            col::download_1_byte_frame(node, SDO_RECEIVE, index, subindex, value as u8)
-                .unwrap()
-                .into()
-        }
-        ValueType::U16 => {
-            let buffer: [u8; 2] = [
-                // little endian encoded
-                (value & 0xff_u32) as u8,
-                ((value >> 8) & 0xff_u32) as u8,
-            ];
-            col::download_2_bytes_frame(node, SDO_RECEIVE, index, subindex, buffer)
-                .unwrap()
-                .into()
-        }
-        ValueType::U32 => {
-            let buffer: [u8; 4] = [
-                // little endian encoded
-                (value & 0xff_u32) as u8,
-                ((value >> 8) & 0xff_u32) as u8,
-                ((value >> 16) & 0xff_u32) as u8,
-                ((value >> 24) & 0xff_u32) as u8,
-            ];
-            col::download_4_bytes_frame(node, SDO_RECEIVE, index, subindex, buffer)
-                .unwrap()
-                .into()
-        }
+    let size = match value_type {
+        ValueType::U8 => 1,
+        ValueType::U16 => 2,
+        ValueType::U32 => 4,
         _ => {
             panic!("{:?} is not supported for this SDO", value_type);
         }
     };
+    // little endian encoded
+    let data = &value.to_le_bytes()[..size];
+    let frame: CANFrame = col::download_frame(node, SDO_RECEIVE, index, subindex, data)
+        .unwrap()
+        .into();
 
     match match can_socket.write_frame(frame) {
         Ok(x) => x,
@@ -69,23 +50,32 @@ pub async fn write_remote_object(
     }
 
     // read the response
-    while let Some(Ok(frame)) = can_socket.next().await {
+    while let Some(frame) = can_socket.next().await {
+        let frame = frame.unwrap_or_else(|error| panic!("Error reading: {}", error));
         match col::CANOpenFrame::try_from(frame) {
             Ok(frame) => {
-                if frame.node_id() == node && frame.frame_type() == col::frame::FrameType::SsdoTx {
+                // a late response to an earlier transfer is not our acknowledgement
+                if frame.node_id() == u8::from(node)
+                    && frame.frame_type() == col::frame::FrameType::SsdoTx
+                    && frame.sdo_index() == Some((index, subindex))
+                {
+                    let acknowledged = SDOServerResponse::parse(&frame)
+                        .and_then(|sdo_response| sdo_response.download_acknowledged());
+                    if let Err(error) = acknowledged {
+                        panic!("SDO download failed: {}", error);
+                    }
                     break;
                 }
             }
-            Err(e) => {
-                panic!("{}", e);
-            }
+            Err(e) if col::is_extended_frame_error(&e) => continue,
+            Err(e) => eprintln!("Skip frame: {}", e),
         }
     }
 }
 
 pub async fn read_remote_object(
     can_socket: &mut CANSocket,
-    node: u8,
+    node: NodeId,
     index: u16,
     subindex: u8,
     expected_value: u32,
@@ -109,23 +99,33 @@ pub async fn read_remote_object(
     }
 
     // read the response
-    while let Some(Ok(frame)) = can_socket.next().await {
+    while let Some(frame) = can_socket.next().await {
+        let frame = frame.unwrap_or_else(|error| panic!("Error reading: {}", error));
         match col::CANOpenFrame::try_from(frame) {
             Ok(frame) => {
-                if frame.node_id() == node && frame.frame_type() == col::frame::FrameType::SsdoTx {
-                    let sdo_response = SDOServerResponse::parse(&frame)
-                        .map_err(|x| panic!("{}", x))
-                        .unwrap();
-                    if sdo_response.index == index && sdo_response.subindex == subindex {
-                        if sdo_response.data as u32 == expected_value {
+                if frame.node_id() != u8::from(node)
+                    || frame.frame_type() != col::frame::FrameType::SsdoTx
+                {
+                    continue;
+                }
+                match frame.sdo_index() {
+                    Some(response_index) if response_index == (index, subindex) => {
+                        let sdo_response = SDOServerResponse::parse(&frame)
+                            .map_err(|x| panic!("{}", x))
+                            .unwrap();
+                        if sdo_response.data == expected_value {
                             break;
                         }
                     }
+                    Some((response_index, response_subindex)) => panic!(
+                        "Unexpected SDO response for object {:#06x},{:#04x}, expected {:#06x},{:#04x}",
+                        response_index, response_subindex, index, subindex
+                    ),
+                    None => (),
                 }
             }
-            Err(e) => {
-                panic!("{}", e);
-            }
+            Err(e) if col::is_extended_frame_error(&e) => continue,
+            Err(e) => eprintln!("Skip frame: {}", e),
         }
     }
 }
@@ -10,12 +10,12 @@ use futures::{
 };
 use futures_timer::Delay;
 use parse_int::parse;
-use tokio;
 use tokio_socketcan::{CANFrame, CANSocket};
 
 use bdd::{read_remote_object, write_remote_object, ValueType};
 use col::{
     self, nodeid_parser, parse_payload_as_byte_sequence_semicolon_delimited, pdo_cobid_parser,
+    ObjectAddress,
 };
 
 async fn play_timeout(timeout_in_ms: u32) -> () {
@@ -68,11 +68,12 @@ async fn wait_some_time(_w: &mut World, time_in_ms: u32) {
 #[given(regex = r".*[Nn]ode (0x[0-9a-fA-F]{2}) is up$")]
 #[then(regex = r".*[Nn]ode (0x[0-9a-fA-F]{2}) is up$")]
 async fn expect_node_sends_nmt_heartbeat(w: &mut World, node: String) {
-    const NMT_ERROR_CONTROL: u32 = 0b1110 << 7;
-    let cob_id = nodeid_parser(&node).unwrap() as u32 + NMT_ERROR_CONTROL;
-    let data: [u8; 1] = [5];
+    let heartbeat =
+        col::heartbeat_frame(nodeid_parser(&node).unwrap(), col::State::Operational).unwrap();
+    let data = heartbeat.data();
+    let payload = &data[0..heartbeat.length() as usize];
 
-    let can_worker = expect_frame(&mut w.cansocket, cob_id, Some(&data)).fuse();
+    let can_worker = expect_frame(&mut w.cansocket, heartbeat.cob_id(), Some(payload)).fuse();
     let timeout_worker = play_timeout(1200).fuse();
 
     pin_mut!(can_worker, timeout_worker);
@@ -107,7 +108,7 @@ async fn response_read_pdo_with_payload(
     let cob_id = pdo_cobid_parser(&cob_id).unwrap();
     let (data, len) = parse_payload_as_byte_sequence_semicolon_delimited(&payload);
 
-    let can_worker = expect_frame(&mut w.cansocket, cob_id.into(), Some(&data[0..len])).fuse();
+    let can_worker = expect_frame(&mut w.cansocket, cob_id, Some(&data[0..len])).fuse();
     let timeout_worker = play_timeout(timeout).fuse();
 
     pin_mut!(can_worker, timeout_worker);
@@ -133,7 +134,7 @@ async fn response_read_pdo_with_payload(
 async fn response_read_pdo(w: &mut World, expect_pdo: String, cob_id: String, timeout: u32) {
     let cob_id = pdo_cobid_parser(&cob_id).unwrap();
 
-    let can_worker = expect_frame(&mut w.cansocket, cob_id.into(), None).fuse();
+    let can_worker = expect_frame(&mut w.cansocket, cob_id, None).fuse();
     let timeout_worker = play_timeout(timeout).fuse();
 
     pin_mut!(can_worker, timeout_worker);
@@ -156,22 +157,20 @@ async fn response_read_pdo(w: &mut World, expect_pdo: String, cob_id: String, ti
 }
 
 #[given(
-    regex = r".*[Ss]et object (0x[0-9a-fA-F]{4}),(0x[0-9a-fA-F]{2}) at node (0x[0-9a-fA-F]{2}) as type (u8|u16|u32) to value ([0-9_xba-fA-F]+)$"
+    regex = r".*[Ss]et object (0x[0-9a-fA-F]{4},0x[0-9a-fA-F]{2}) at node (0x[0-9a-fA-F]{2}) as type (u8|u16|u32) to value ([0-9_xba-fA-F]+)$"
 )]
 #[when(
-    regex = r".*[Ss]et object (0x[0-9a-fA-F]{4}),(0x[0-9a-fA-F]{2}) at node (0x[0-9a-fA-F]{2}) as type (u8|u16|u32) to value ([0-9_xba-fA-F]+)$"
+    regex = r".*[Ss]et object (0x[0-9a-fA-F]{4},0x[0-9a-fA-F]{2}) at node (0x[0-9a-fA-F]{2}) as type (u8|u16|u32) to value ([0-9_xba-fA-F]+)$"
 )]
 async fn write_object_at_node(
     w: &mut World,
-    index: String,
-    subindex: String,
+    address: String,
     node: String,
     data_type: String,
     payload: String,
 ) {
     let node_id = nodeid_parser(&node).unwrap();
-    let index = parse::<u16>(&index).unwrap();
-    let subindex = parse::<u8>(&subindex).unwrap();
+    let address: ObjectAddress = address.parse().unwrap();
     let value = parse::<u32>(&payload).unwrap() as u32;
     let value_type = match data_type.as_str() {
         "u8" => ValueType::U8,
@@ -182,9 +181,9 @@ async fn write_object_at_node(
 
     let can_worker = write_remote_object(
         &mut w.cansocket,
-        node_id as u8,
-        index,
-        subindex,
+        node_id,
+        address.index,
+        address.subindex,
         value_type,
         value,
     )
@@ -200,28 +199,21 @@ async fn write_object_at_node(
 }
 
 #[given(
-    regex = r".*[Ee]xpect object (0x[0-9a-fA-F]{4}),(0x[0-9a-fA-F]{2}) at node (0x[0-9a-fA-F]{2}) to be ([0-9_xa-fA-F]+)$"
+    regex = r".*[Ee]xpect object (0x[0-9a-fA-F]{4},0x[0-9a-fA-F]{2}) at node (0x[0-9a-fA-F]{2}) to be ([0-9_xa-fA-F]+)$"
 )]
 #[then(
-    regex = r".*[Ee]xpect object (0x[0-9a-fA-F]{4}),(0x[0-9a-fA-F]{2}) at node (0x[0-9a-fA-F]{2}) to be ([0-9_xa-fA-F]+)$"
+    regex = r".*[Ee]xpect object (0x[0-9a-fA-F]{4},0x[0-9a-fA-F]{2}) at node (0x[0-9a-fA-F]{2}) to be ([0-9_xa-fA-F]+)$"
 )]
-async fn read_object_at_node(
-    w: &mut World,
-    index: String,
-    subindex: String,
-    node: String,
-    payload: String,
-) {
+async fn read_object_at_node(w: &mut World, address: String, node: String, payload: String) {
     let node_id = nodeid_parser(&node).unwrap();
-    let index = parse::<u16>(&index).unwrap();
-    let subindex = parse::<u8>(&subindex).unwrap();
+    let address: ObjectAddress = address.parse().unwrap();
     let expected_value = parse::<u32>(&payload).unwrap();
 
     let can_worker = read_remote_object(
         &mut w.cansocket,
-        node_id as u8,
-        index,
-        subindex,
+        node_id,
+        address.index,
+        address.subindex,
         expected_value,
     )
     .fuse();
@@ -1,4 +1,6 @@
-use byteorder::{LittleEndian, WriteBytesExt};
+#![allow(non_local_definitions)]
+
+use byteorder::{ByteOrder, LittleEndian, WriteBytesExt};
 use encoding::all::ASCII;
 use encoding::{EncoderTrap, Encoding};
 use failure::{Error, Fail};
@@ -14,6 +16,10 @@ pub enum DataConversionError {
     InvalidDataType(u32),
     #[fail(display = "mismatching data type")]
     MismatchingDataType,
+    #[fail(display = "{} bytes do not encode a {:?}", _0, _1)]
+    InvalidDataLength(usize, DataType),
+    #[fail(display = "{} bytes do not fit into a buffer of {} bytes", _0, _1)]
+    BufferTooSmall(usize, usize),
 }
 
 #[allow(non_camel_case_types, dead_code)]
@@ -48,6 +54,32 @@ pub enum DataType {
     DOMAIN,
 }
 
+impl DataType {
+    /// Size of the encoded value in bits, `None` for variable sized types
+    pub fn bit_size(&self) -> Option<usize> {
+        match self {
+            DataType::NIL => Some(0),
+            DataType::BOOLEAN => Some(1),
+            DataType::UNSIGNED8 | DataType::INTEGER8 => Some(8),
+            DataType::UNSIGNED16 | DataType::INTEGER16 => Some(16),
+            DataType::UNSIGNED24 | DataType::INTEGER24 => Some(24),
+            DataType::UNSIGNED32 | DataType::INTEGER32 | DataType::REAL32 => Some(32),
+            DataType::UNSIGNED40 | DataType::INTEGER40 => Some(40),
+            DataType::UNSIGNED48
+            | DataType::INTEGER48
+            | DataType::TIMEOFDAY
+            | DataType::TIMEDIFFERENCE => Some(48),
+            DataType::UNSIGNED56 | DataType::INTEGER56 => Some(56),
+            DataType::UNSIGNED64 | DataType::INTEGER64 | DataType::REAL64 => Some(64),
+            DataType::VOID
+            | DataType::OCTETSTRING
+            | DataType::VISIBLESTRING
+            | DataType::UNICODESTRING
+            | DataType::DOMAIN => None,
+        }
+    }
+}
+
 #[allow(non_camel_case_types, dead_code)]
 #[derive(Debug, PartialEq, Clone)]
 pub enum Data {
@@ -88,19 +120,28 @@ impl From<Data> for DataType {
             Data::VOID(_) => DataType::VOID,
             Data::UNSIGNED8(_) => DataType::UNSIGNED8,
             Data::UNSIGNED16(_) => DataType::UNSIGNED16,
+            Data::UNSIGNED24(_) => DataType::UNSIGNED24,
             Data::UNSIGNED32(_) => DataType::UNSIGNED32,
+            Data::UNSIGNED40(_) => DataType::UNSIGNED40,
+            Data::UNSIGNED48(_) => DataType::UNSIGNED48,
+            Data::UNSIGNED56(_) => DataType::UNSIGNED56,
             Data::UNSIGNED64(_) => DataType::UNSIGNED64,
             Data::INTEGER8(_) => DataType::INTEGER8,
             Data::INTEGER16(_) => DataType::INTEGER16,
+            Data::INTEGER24(_) => DataType::INTEGER24,
             Data::INTEGER32(_) => DataType::INTEGER32,
+            Data::INTEGER40(_) => DataType::INTEGER40,
+            Data::INTEGER48(_) => DataType::INTEGER48,
+            Data::INTEGER56(_) => DataType::INTEGER56,
             Data::INTEGER64(_) => DataType::INTEGER64,
             Data::REAL32(_) => DataType::REAL32,
             Data::REAL64(_) => DataType::REAL64,
             Data::OCTETSTRING(_) => DataType::OCTETSTRING,
             Data::VISIBLESTRING(_) => DataType::VISIBLESTRING,
             Data::UNICODESTRING(_) => DataType::UNICODESTRING,
+            Data::TIMEOFDAY(_) => DataType::TIMEOFDAY,
+            Data::TIMEDIFFERENCE(_) => DataType::TIMEDIFFERENCE,
             Data::DOMAIN(_) => DataType::DOMAIN,
-            _ => unimplemented!(),
         }
     }
 }
@@ -168,25 +209,132 @@ impl Data {
             Data::VOID(length) => *length,
             Data::UNSIGNED8(_) => 8,
             Data::UNSIGNED16(_) => 16,
+            Data::UNSIGNED24(_) => 24,
             Data::UNSIGNED32(_) => 32,
+            Data::UNSIGNED40(_) => 40,
+            Data::UNSIGNED48(_) => 48,
+            Data::UNSIGNED56(_) => 56,
             Data::UNSIGNED64(_) => 64,
             Data::INTEGER8(_) => 8,
             Data::INTEGER16(_) => 16,
+            Data::INTEGER24(_) => 24,
             Data::INTEGER32(_) => 32,
+            Data::INTEGER40(_) => 40,
+            Data::INTEGER48(_) => 48,
+            Data::INTEGER56(_) => 56,
             Data::INTEGER64(_) => 64,
             Data::REAL32(_) => 32,
             Data::REAL64(_) => 64,
             Data::OCTETSTRING(value) => value.len(),
             Data::VISIBLESTRING(value) => value.len(),
             Data::UNICODESTRING(value) => value.len(),
+            Data::TIMEOFDAY(_) => 48,
+            Data::TIMEDIFFERENCE(_) => 48,
             Data::DOMAIN(value) => value.len(),
-            _ => unimplemented!(),
         }
     }
 
     pub fn is_empty(&self) -> bool {
         *self == Data::NIL
     }
+
+    fn as_i128(&self) -> Option<i128> {
+        Some(match *self {
+            Data::BOOLEAN(value) => value as i128,
+            Data::UNSIGNED8(value) => value as i128,
+            Data::UNSIGNED16(value) => value as i128,
+            Data::UNSIGNED24(value) => value as i128,
+            Data::UNSIGNED32(value) => value as i128,
+            Data::UNSIGNED40(value)
+            | Data::UNSIGNED48(value)
+            | Data::UNSIGNED56(value)
+            | Data::UNSIGNED64(value) => value as i128,
+            Data::INTEGER8(value) => value as i128,
+            Data::INTEGER16(value) => value as i128,
+            Data::INTEGER24(value) | Data::INTEGER32(value) => value as i128,
+            Data::INTEGER40(value)
+            | Data::INTEGER48(value)
+            | Data::INTEGER56(value)
+            | Data::INTEGER64(value) => value as i128,
+            _ => return None,
+        })
+    }
+
+    /// Integer value widened to u64, `None` for negative, real or non numeric values
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_i128().and_then(|value| u64::try_from(value).ok())
+    }
+
+    /// Integer value widened to i64, `None` if out of range, real or non numeric
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_i128().and_then(|value| i64::try_from(value).ok())
+    }
+
+    /// Any numeric value as f64, `None` for non numeric values
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Data::REAL32(value) => Some(value as f64),
+            Data::REAL64(value) => Some(value),
+            _ => self.as_i128().map(|value| value as f64),
+        }
+    }
+
+    /// Compare the numeric values of two possibly different variants,
+    /// `None` if either is not numeric
+    pub fn numeric_cmp(&self, other: &Data) -> Option<std::cmp::Ordering> {
+        match (self.as_i128(), other.as_i128()) {
+            (Some(a), Some(b)) => Some(a.cmp(&b)),
+            _ => self.as_f64()?.partial_cmp(&other.as_f64()?),
+        }
+    }
+}
+
+// plain value without the variant name, `{:#}` selects hex for integers
+// and quotes strings
+impl std::fmt::Display for Data {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Data::NIL => write!(f, "nil"),
+            Data::BOOLEAN(value) => write!(f, "{}", value),
+            Data::VOID(length) => write!(f, "void({})", length),
+            Data::REAL32(value) => write!(f, "{}", value),
+            Data::REAL64(value) => write!(f, "{}", value),
+            Data::VISIBLESTRING(value) if f.alternate() => {
+                write!(f, "{:?}", String::from_utf8_lossy(value))
+            }
+            Data::VISIBLESTRING(value) => write!(f, "{}", String::from_utf8_lossy(value)),
+            Data::UNICODESTRING(value) if f.alternate() => write!(f, "{:?}", value),
+            Data::UNICODESTRING(value) => write!(f, "{}", value),
+            Data::OCTETSTRING(value) | Data::DOMAIN(value) => {
+                let bytes: Vec<String> = value.iter().map(|b| format!("{:02x}", b)).collect();
+                write!(f, "{}", bytes.join(" "))
+            }
+            Data::TIMEOFDAY(value) => write!(f, "{:?}", value),
+            Data::TIMEDIFFERENCE(value) => write!(f, "{:?}", value),
+            Data::INTEGER8(value) if !f.alternate() => write!(f, "{}", value),
+            Data::INTEGER16(value) if !f.alternate() => write!(f, "{}", value),
+            Data::INTEGER24(value) | Data::INTEGER32(value) if !f.alternate() => {
+                write!(f, "{}", value)
+            }
+            Data::INTEGER40(value)
+            | Data::INTEGER48(value)
+            | Data::INTEGER56(value)
+            | Data::INTEGER64(value)
+                if !f.alternate() =>
+            {
+                write!(f, "{}", value)
+            }
+            _ => match self.as_u64() {
+                Some(value) if f.alternate() => write!(f, "{:#x}", value),
+                Some(value) => write!(f, "{}", value),
+                // negative integers in hex, as two's complement of the type size
+                None => {
+                    let bytes = Vec::<u8>::try_from(self.clone()).map_err(|_| std::fmt::Error)?;
+                    write!(f, "{:#x}", LittleEndian::read_uint(&bytes, bytes.len()))
+                }
+            },
+        }
+    }
 }
 
 impl std::str::FromStr for DataType {
@@ -232,6 +380,12 @@ impl TryFrom<u32> for DataType {
     }
 }
 
+// two's complement truncated to the given number of bytes
+fn truncate(value: u64, bytes: usize) -> u64 {
+    value & (u64::MAX >> (64 - 8 * bytes))
+}
+
+// CANopen encodes numeric values little endian on the wire
 impl TryFrom<Data> for Vec<u8> {
     type Error = Error;
 
@@ -245,11 +399,29 @@ impl TryFrom<Data> for Vec<u8> {
             Data::VOID(length) => bytes = vec![0u8; length],
             Data::UNSIGNED8(value) => bytes.write_u8(value)?,
             Data::UNSIGNED16(value) => bytes.write_u16::<LittleEndian>(value)?,
+            Data::UNSIGNED24(value) => {
+                bytes.write_uint::<LittleEndian>(truncate(value as u64, 3), 3)?
+            }
             Data::UNSIGNED32(value) => bytes.write_u32::<LittleEndian>(value)?,
+            Data::UNSIGNED40(value) => bytes.write_uint::<LittleEndian>(truncate(value, 5), 5)?,
+            Data::UNSIGNED48(value) => bytes.write_uint::<LittleEndian>(truncate(value, 6), 6)?,
+            Data::UNSIGNED56(value) => bytes.write_uint::<LittleEndian>(truncate(value, 7), 7)?,
             Data::UNSIGNED64(value) => bytes.write_u64::<LittleEndian>(value)?,
             Data::INTEGER8(value) => bytes.write_i8(value)?,
             Data::INTEGER16(value) => bytes.write_i16::<LittleEndian>(value)?,
+            Data::INTEGER24(value) => {
+                bytes.write_uint::<LittleEndian>(truncate(value as u64, 3), 3)?
+            }
             Data::INTEGER32(value) => bytes.write_i32::<LittleEndian>(value)?,
+            Data::INTEGER40(value) => {
+                bytes.write_uint::<LittleEndian>(truncate(value as u64, 5), 5)?
+            }
+            Data::INTEGER48(value) => {
+                bytes.write_uint::<LittleEndian>(truncate(value as u64, 6), 6)?
+            }
+            Data::INTEGER56(value) => {
+                bytes.write_uint::<LittleEndian>(truncate(value as u64, 7), 7)?
+            }
             Data::INTEGER64(value) => bytes.write_i64::<LittleEndian>(value)?,
             Data::REAL32(value) => bytes.write_f32::<LittleEndian>(value)?,
             Data::REAL64(value) => bytes.write_f64::<LittleEndian>(value)?,
@@ -257,7 +429,9 @@ impl TryFrom<Data> for Vec<u8> {
             Data::VISIBLESTRING(value) => bytes = value,
             Data::UNICODESTRING(value) => bytes = value.as_bytes().to_vec(),
             Data::DOMAIN(value) => bytes = value,
-            _ => unimplemented!(),
+            Data::TIMEOFDAY(_) | Data::TIMEDIFFERENCE(_) => {
+                return Err(DataConversionError::MismatchingDataType.into())
+            }
         };
 
         Ok(bytes)
@@ -265,13 +439,78 @@ impl TryFrom<Data> for Vec<u8> {
 }
 
 impl Data {
+    /// Encode little endian into the given buffer and return the used part,
+    /// fails instead of panicking if the buffer is too small
+    pub fn try_to_little_endian_buffer<'a>(&self, buf: &'a mut [u8]) -> Result<&'a [u8]> {
+        let bytes = Vec::<u8>::try_from(self.clone())?;
+        if bytes.len() > buf.len() {
+            return Err(DataConversionError::BufferTooSmall(bytes.len(), buf.len()).into());
+        }
+        buf[..bytes.len()].copy_from_slice(&bytes);
+        Ok(&buf[..bytes.len()])
+    }
+
+    /// Encode numeric values big endian (byte order reversed compared to the
+    /// CANopen wire format), strings and domains are left as they are
+    pub fn to_be_bytes(&self) -> Result<Vec<u8>> {
+        let mut bytes = Vec::<u8>::try_from(self.clone())?;
+        match self {
+            Data::OCTETSTRING(_)
+            | Data::VISIBLESTRING(_)
+            | Data::UNICODESTRING(_)
+            | Data::DOMAIN(_) => {}
+            _ => bytes.reverse(),
+        }
+        Ok(bytes)
+    }
+
+    /// Decode little endian encoded bytes as given by the data type,
+    /// the number of bytes must match the size of fixed sized types
+    pub fn from_le_bytes(data: &[u8], data_type: DataType) -> Result<Self> {
+        let n = data.len();
+        if let Some(bits) = data_type.bit_size() {
+            if n != bits.div_ceil(8) {
+                return Err(DataConversionError::InvalidDataLength(n, data_type).into());
+            }
+        }
+        Ok(match data_type {
+            DataType::NIL => Data::NIL,
+            DataType::BOOLEAN => Data::BOOLEAN(data[0] != 0),
+            DataType::UNSIGNED8 => Data::UNSIGNED8(data[0]),
+            DataType::UNSIGNED16 => Data::UNSIGNED16(LittleEndian::read_u16(data)),
+            DataType::UNSIGNED24 => Data::UNSIGNED24(LittleEndian::read_uint(data, n) as i32),
+            DataType::UNSIGNED32 => Data::UNSIGNED32(LittleEndian::read_u32(data)),
+            DataType::UNSIGNED40 => Data::UNSIGNED40(LittleEndian::read_uint(data, n)),
+            DataType::UNSIGNED48 => Data::UNSIGNED48(LittleEndian::read_uint(data, n)),
+            DataType::UNSIGNED56 => Data::UNSIGNED56(LittleEndian::read_uint(data, n)),
+            DataType::UNSIGNED64 => Data::UNSIGNED64(LittleEndian::read_u64(data)),
+            DataType::INTEGER8 => Data::INTEGER8(data[0] as i8),
+            DataType::INTEGER16 => Data::INTEGER16(LittleEndian::read_i16(data)),
+            DataType::INTEGER24 => Data::INTEGER24(LittleEndian::read_int(data, n) as i32),
+            DataType::INTEGER32 => Data::INTEGER32(LittleEndian::read_i32(data)),
+            DataType::INTEGER40 => Data::INTEGER40(LittleEndian::read_int(data, n)),
+            DataType::INTEGER48 => Data::INTEGER48(LittleEndian::read_int(data, n)),
+            DataType::INTEGER56 => Data::INTEGER56(LittleEndian::read_int(data, n)),
+            DataType::INTEGER64 => Data::INTEGER64(LittleEndian::read_i64(data)),
+            DataType::REAL32 => Data::REAL32(LittleEndian::read_f32(data)),
+            DataType::REAL64 => Data::REAL64(LittleEndian::read_f64(data)),
+            DataType::OCTETSTRING => Data::OCTETSTRING(data.to_vec()),
+            DataType::VISIBLESTRING => Data::VISIBLESTRING(data.to_vec()),
+            DataType::UNICODESTRING => Data::UNICODESTRING(String::from_utf8(data.to_vec())?),
+            DataType::DOMAIN => Data::DOMAIN(data.to_vec()),
+            DataType::VOID | DataType::TIMEOFDAY | DataType::TIMEDIFFERENCE => {
+                return Err(DataConversionError::MismatchingDataType.into())
+            }
+        })
+    }
+
     pub fn from_str(value: &str, data_type: DataType) -> Result<Self> {
         Ok(match data_type {
             DataType::NIL => Data::NIL,
             DataType::BOOLEAN => match value {
-                "0" => Data::BOOLEAN(false),
-                "1" => Data::BOOLEAN(true),
-                &_ => panic!("invalid boolean format"),
+                "0" | "false" => Data::BOOLEAN(false),
+                "1" | "true" => Data::BOOLEAN(true),
+                &_ => return Err(DataConversionError::MismatchingDataType.into()),
             },
             DataType::VOID => unimplemented!(),
             DataType::UNSIGNED8 => Data::UNSIGNED8(
@@ -331,7 +570,7 @@ pub trait AsNum {
         <T as Num>::FromStrRadixErr: 'static;
 }
 
-impl<'a> AsNum for &'a str {
+impl AsNum for &str {
     fn as_num<T>(&self) -> Result<T>
     where
         T: Num,
@@ -349,3 +588,179 @@ impl<'a> AsNum for &'a str {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cmp::Ordering;
+
+    #[test]
+    fn test_widening() {
+        assert_eq!(Some(200), Data::UNSIGNED8(200).as_u64());
+        assert_eq!(Some(200), Data::UNSIGNED8(200).as_i64());
+        assert_eq!(None, Data::INTEGER16(-1).as_u64());
+        assert_eq!(Some(-1), Data::INTEGER16(-1).as_i64());
+        assert_eq!(None, Data::UNSIGNED64(u64::MAX).as_i64());
+        assert_eq!(Some(u64::MAX), Data::UNSIGNED64(u64::MAX).as_u64());
+        assert_eq!(None, Data::REAL32(1.5).as_u64());
+        assert_eq!(Some(1.5), Data::REAL32(1.5).as_f64());
+        assert_eq!(Some(-3.0), Data::INTEGER8(-3).as_f64());
+        assert_eq!(None, Data::VISIBLESTRING(b"1".to_vec()).as_f64());
+    }
+
+    #[test]
+    fn test_little_endian_bytes() {
+        let bytes = |data: Data| Vec::<u8>::try_from(data).unwrap();
+        assert_eq!(vec![0x12], bytes(Data::UNSIGNED8(0x12)));
+        assert_eq!(vec![0x34, 0x12], bytes(Data::UNSIGNED16(0x1234)));
+        assert_eq!(vec![0x56, 0x34, 0x12], bytes(Data::UNSIGNED24(0x123456)));
+        assert_eq!(
+            vec![0x78, 0x56, 0x34, 0x12],
+            bytes(Data::UNSIGNED32(0x12345678))
+        );
+        assert_eq!(vec![0xFE, 0xFF, 0xFF], bytes(Data::INTEGER24(-2)));
+        assert_eq!(vec![0, 0, 0, 0, 1], bytes(Data::UNSIGNED40(0x01_0000_0000)));
+        assert_eq!(vec![0x00, 0x00, 0x80, 0x3F], bytes(Data::REAL32(1.0)));
+        assert_eq!(b"abc".to_vec(), bytes(Data::VISIBLESTRING(b"abc".to_vec())));
+    }
+
+    #[test]
+    fn test_big_endian_bytes() {
+        let bytes = |data: Data| data.to_be_bytes().unwrap();
+        assert_eq!(vec![0x12], bytes(Data::UNSIGNED8(0x12)));
+        assert_eq!(vec![0x12, 0x34], bytes(Data::UNSIGNED16(0x1234)));
+        assert_eq!(vec![0x12, 0x34, 0x56], bytes(Data::UNSIGNED24(0x123456)));
+        assert_eq!(
+            vec![0x12, 0x34, 0x56, 0x78],
+            bytes(Data::UNSIGNED32(0x12345678))
+        );
+        assert_eq!(vec![0xFF, 0xFF, 0xFE], bytes(Data::INTEGER24(-2)));
+        assert_eq!(vec![1, 0, 0, 0, 0], bytes(Data::UNSIGNED40(0x01_0000_0000)));
+        assert_eq!(vec![0x3F, 0x80, 0x00, 0x00], bytes(Data::REAL32(1.0)));
+        assert_eq!(b"abc".to_vec(), bytes(Data::VISIBLESTRING(b"abc".to_vec())));
+    }
+
+    #[test]
+    fn test_try_to_little_endian_buffer() {
+        let mut buf = [0u8; 4];
+        assert_eq!(
+            &[0x34, 0x12],
+            Data::UNSIGNED16(0x1234)
+                .try_to_little_endian_buffer(&mut buf)
+                .unwrap()
+        );
+        assert!(Data::UNSIGNED64(1)
+            .try_to_little_endian_buffer(&mut buf)
+            .is_err());
+        assert!(Data::VISIBLESTRING(b"too long".to_vec())
+            .try_to_little_endian_buffer(&mut buf)
+            .is_err());
+        assert!(Data::TIMEDIFFERENCE(Duration::from_secs(1))
+            .try_to_little_endian_buffer(&mut buf)
+            .is_err());
+    }
+
+    #[test]
+    fn test_from_le_bytes() {
+        assert_eq!(
+            Data::UNSIGNED16(0x1234),
+            Data::from_le_bytes(&[0x34, 0x12], DataType::UNSIGNED16).unwrap()
+        );
+        assert_eq!(
+            Data::UNSIGNED24(0x123456),
+            Data::from_le_bytes(&[0x56, 0x34, 0x12], DataType::UNSIGNED24).unwrap()
+        );
+        assert_eq!(
+            Data::INTEGER24(-2),
+            Data::from_le_bytes(&[0xFE, 0xFF, 0xFF], DataType::INTEGER24).unwrap()
+        );
+        assert_eq!(
+            Data::UNSIGNED40(0x01_0000_0000),
+            Data::from_le_bytes(&[0, 0, 0, 0, 1], DataType::UNSIGNED40).unwrap()
+        );
+        assert_eq!(
+            Data::INTEGER56(-1),
+            Data::from_le_bytes(&[0xFF; 7], DataType::INTEGER56).unwrap()
+        );
+        assert_eq!(
+            Data::REAL32(1.0),
+            Data::from_le_bytes(&[0x00, 0x00, 0x80, 0x3F], DataType::REAL32).unwrap()
+        );
+        assert_eq!(
+            Data::VISIBLESTRING(b"canopen".to_vec()),
+            Data::from_le_bytes(b"canopen", DataType::VISIBLESTRING).unwrap()
+        );
+        for data_type in [
+            DataType::UNSIGNED24,
+            DataType::UNSIGNED40,
+            DataType::UNSIGNED48,
+            DataType::UNSIGNED56,
+            DataType::INTEGER24,
+            DataType::INTEGER40,
+            DataType::INTEGER48,
+            DataType::INTEGER56,
+        ] {
+            let bits = data_type.bit_size().unwrap();
+            let data = Data::from_le_bytes(&[0x01; 7][..bits / 8], data_type).unwrap();
+            assert_eq!(bits, data.len());
+            assert_eq!(data_type, DataType::from(data));
+        }
+        assert!(Data::from_le_bytes(&[0x34, 0x12, 0x00], DataType::UNSIGNED16).is_err());
+        assert!(Data::from_le_bytes(&[], DataType::UNSIGNED8).is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!("305419896", Data::UNSIGNED32(0x12345678).to_string());
+        assert_eq!("0x12345678", format!("{:#}", Data::UNSIGNED32(0x12345678)));
+        assert_eq!("-2", Data::INTEGER16(-2).to_string());
+        assert_eq!("0xfffe", format!("{:#}", Data::INTEGER16(-2)));
+        assert_eq!("true", Data::BOOLEAN(true).to_string());
+        assert_eq!("1.5", Data::REAL32(1.5).to_string());
+        assert_eq!("abc", Data::VISIBLESTRING(b"abc".to_vec()).to_string());
+        assert_eq!(
+            "\"abc\"",
+            format!("{:#}", Data::UNICODESTRING("abc".to_string()))
+        );
+        assert_eq!("01 ab", Data::OCTETSTRING(vec![0x01, 0xab]).to_string());
+    }
+
+    #[test]
+    fn test_boolean() {
+        assert_eq!(
+            Data::BOOLEAN(true),
+            Data::from_str("true", DataType::BOOLEAN).unwrap()
+        );
+        assert_eq!(
+            Data::BOOLEAN(false),
+            Data::from_str("0", DataType::BOOLEAN).unwrap()
+        );
+        assert!(Data::from_str("yes", DataType::BOOLEAN).is_err());
+        assert_eq!(vec![1], Vec::<u8>::try_from(Data::BOOLEAN(true)).unwrap());
+        assert_eq!(DataType::BOOLEAN, DataType::from(Data::BOOLEAN(false)));
+        assert_eq!(
+            Data::BOOLEAN(true),
+            Data::from_le_bytes(&[1], DataType::BOOLEAN).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_numeric_cmp() {
+        assert_eq!(
+            Some(Ordering::Equal),
+            Data::UNSIGNED8(1).numeric_cmp(&Data::UNSIGNED32(1))
+        );
+        assert_eq!(
+            Some(Ordering::Less),
+            Data::INTEGER64(-1).numeric_cmp(&Data::UNSIGNED64(u64::MAX))
+        );
+        assert_eq!(
+            Some(Ordering::Greater),
+            Data::REAL64(2.5).numeric_cmp(&Data::UNSIGNED16(2))
+        );
+        assert_eq!(
+            None,
+            Data::UNSIGNED8(1).numeric_cmp(&Data::VISIBLESTRING(b"1".to_vec()))
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use std::fmt;
+
+use crate::frame::*;
+
+/// Error code class of an emergency message, given by the high byte
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum EmcyCategory {
+    ErrorReset,
+    Generic,
+    Current,
+    Voltage,
+    Temperature,
+    Hardware,
+    Software,
+    AdditionalModules,
+    Monitoring,
+    External,
+    DeviceSpecific,
+    Unknown,
+}
+
+impl From<u16> for EmcyCategory {
+    fn from(error_code: u16) -> Self {
+        match error_code >> 8 {
+            0x00 => EmcyCategory::ErrorReset,
+            0x10 => EmcyCategory::Generic,
+            0x20..=0x23 => EmcyCategory::Current,
+            0x30..=0x33 => EmcyCategory::Voltage,
+            0x40..=0x42 => EmcyCategory::Temperature,
+            0x50 => EmcyCategory::Hardware,
+            0x60..=0x63 => EmcyCategory::Software,
+            0x70 => EmcyCategory::AdditionalModules,
+            0x80..=0x82 => EmcyCategory::Monitoring,
+            0x90 => EmcyCategory::External,
+            0xf0 | 0xff => EmcyCategory::DeviceSpecific,
+            _ => EmcyCategory::Unknown,
+        }
+    }
+}
+
+impl fmt::Display for EmcyCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            EmcyCategory::ErrorReset => "error reset or no error",
+            EmcyCategory::Generic => "generic error",
+            EmcyCategory::Current => "current",
+            EmcyCategory::Voltage => "voltage",
+            EmcyCategory::Temperature => "temperature",
+            EmcyCategory::Hardware => "device hardware",
+            EmcyCategory::Software => "device software",
+            EmcyCategory::AdditionalModules => "additional modules",
+            EmcyCategory::Monitoring => "monitoring",
+            EmcyCategory::External => "external error",
+            EmcyCategory::DeviceSpecific => "device specific",
+            EmcyCategory::Unknown => "unknown",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Content of an emergency message
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct EmergencyPayload {
+    pub error_code: u16,
+    pub error_register: u8,
+    pub data: [u8; 5],
+}
+
+impl EmergencyPayload {
+    /// Decode an emergency frame; sync frames (cob id 0x80) and short frames yield `None`
+    pub fn from_frame(frame: &CANOpenFrame) -> Option<Self> {
+        if frame.frame_type() != FrameType::SyncEmergency
+            || frame.node_id() == 0
+            || frame.length() < 3
+        {
+            return None;
+        }
+        let data = frame.data();
+        Some(EmergencyPayload {
+            error_code: u16::from_le_bytes([data[0], data[1]]),
+            error_register: data[2],
+            data: [data[3], data[4], data[5], data[6], data[7]],
+        })
+    }
+
+    pub fn category(&self) -> EmcyCategory {
+        self.error_code.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_category() {
+        assert_eq!(EmcyCategory::ErrorReset, EmcyCategory::from(0x0000));
+        assert_eq!(EmcyCategory::Generic, EmcyCategory::from(0x1000));
+        assert_eq!(EmcyCategory::Current, EmcyCategory::from(0x2310));
+        assert_eq!(EmcyCategory::Voltage, EmcyCategory::from(0x3210));
+        assert_eq!(EmcyCategory::Temperature, EmcyCategory::from(0x4210));
+        assert_eq!(EmcyCategory::Hardware, EmcyCategory::from(0x5000));
+        assert_eq!(EmcyCategory::Software, EmcyCategory::from(0x6100));
+        assert_eq!(EmcyCategory::AdditionalModules, EmcyCategory::from(0x7000));
+        assert_eq!(EmcyCategory::Monitoring, EmcyCategory::from(0x8130));
+        assert_eq!(EmcyCategory::External, EmcyCategory::from(0x9000));
+        assert_eq!(EmcyCategory::DeviceSpecific, EmcyCategory::from(0xff00));
+        assert_eq!(EmcyCategory::Unknown, EmcyCategory::from(0xa000));
+    }
+
+    #[test]
+    fn test_from_frame() {
+        let frame = emergency_frame(node(0x05), 0x8130, 0x11, [1, 2, 3, 4, 5]).unwrap();
+        let payload = EmergencyPayload::from_frame(&frame).unwrap();
+        assert_eq!(0x8130, payload.error_code);
+        assert_eq!(0x11, payload.error_register);
+        assert_eq!([1, 2, 3, 4, 5], payload.data);
+        assert_eq!(EmcyCategory::Monitoring, payload.category());
+
+        let sync = CANOpenFrame::new(0x80, &[]).unwrap();
+        assert_eq!(None, EmergencyPayload::from_frame(&sync));
+    }
+}
@@ -1,9 +1,21 @@
+//! CANOpen application layer: data types, SDO, PDO and emergency payload
+//! handling and device profiles.
+//!
+//! This module is maintained and builds on the frame layer in `crate::frame`.
+//! Everything here is re-exported at the crate root.
+
 use crate::frame::*;
 
 pub mod data_type;
+pub mod emergency;
+pub mod object_address;
+pub mod pdo;
+pub mod profile;
 pub mod sdo;
 
 pub use self::data_type::*;
-// pub use self::pdo::*;
+pub use self::emergency::*;
+pub use self::object_address::*;
+pub use self::pdo::*;
+pub use self::profile::*;
 pub use self::sdo::*;
-// pub use self::sdo_server::*;
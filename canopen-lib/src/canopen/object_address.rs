@@ -0,0 +1,85 @@
+#![allow(non_local_definitions)]
+
+use failure::{Error, Fail};
+use parse_int::parse;
+use std::fmt;
+
+#[derive(Fail, Debug)]
+pub enum ObjectAddressError {
+    #[fail(display = "invalid object address: {}", _0)]
+    InvalidAddress(String),
+}
+
+/// Index and subindex of an object in the object dictionary
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ObjectAddress {
+    pub index: u16,
+    pub subindex: u8,
+}
+
+impl ObjectAddress {
+    pub fn new(index: u16, subindex: u8) -> Self {
+        ObjectAddress { index, subindex }
+    }
+
+    /// PDO mapping entry value: index, subindex and length in bits
+    pub fn to_mapping(&self, length: u8) -> u32 {
+        ((self.index as u32) << 16) | ((self.subindex as u32) << 8) | length as u32
+    }
+
+    /// Split a PDO mapping entry value into object address and length in bits
+    pub fn from_mapping(value: u32) -> (Self, u8) {
+        (
+            ObjectAddress::new((value >> 16) as u16, (value >> 8) as u8),
+            value as u8,
+        )
+    }
+}
+
+// "0x2000,0x01" or "0x2000" for subindex 0
+impl std::str::FromStr for ObjectAddress {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ObjectAddressError::InvalidAddress(s.to_string());
+        let (index, subindex) = match s.split_once(',') {
+            Some((index, subindex)) => (index, subindex),
+            None => (s, "0"),
+        };
+        Ok(ObjectAddress {
+            index: parse::<u16>(index.trim()).map_err(|_| invalid())?,
+            subindex: parse::<u8>(subindex.trim()).map_err(|_| invalid())?,
+        })
+    }
+}
+
+impl fmt::Display for ObjectAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#06x},{:#04x}", self.index, self.subindex)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display() {
+        let address: ObjectAddress = "0x2000,0x01".parse().unwrap();
+        assert_eq!(ObjectAddress::new(0x2000, 0x01), address);
+        assert_eq!("0x2000,0x01", address.to_string());
+        assert_eq!(
+            ObjectAddress::new(0x1017, 0),
+            "0x1017".parse::<ObjectAddress>().unwrap()
+        );
+        assert!("0x2000,0x100".parse::<ObjectAddress>().is_err());
+        assert!("index".parse::<ObjectAddress>().is_err());
+    }
+
+    #[test]
+    fn test_mapping() {
+        let address = ObjectAddress::new(0x6000, 0x01);
+        assert_eq!(0x6000_0108, address.to_mapping(8));
+        assert_eq!((address, 8), ObjectAddress::from_mapping(0x6000_0108));
+    }
+}
@@ -0,0 +1,503 @@
+#![allow(non_local_definitions)]
+
+use super::*;
+use crate::interface::CanInterface;
+use byteorder::{ByteOrder, LittleEndian};
+use failure::{Error, Fail};
+
+#[derive(Fail, Debug)]
+pub enum PdoError {
+    #[fail(display = "COB-ID {:#x} is not a PDO", cob_id)]
+    NotAPdo { cob_id: u32 },
+    #[fail(
+        display = "COB-ID {:#x} is not a TPDO, only TPDOs can be requested",
+        cob_id
+    )]
+    NotATpdo { cob_id: u32 },
+    #[fail(display = "mapped objects exceed the PDO length ({} > 64 bits)", bits)]
+    PdoOverflow { bits: usize },
+    #[fail(display = "{} values given for {} mapped objects", values, entries)]
+    ValueCountMismatch { values: usize, entries: usize },
+    #[fail(display = "object {:#06x},{:#04x} cannot be mapped", index, subindex)]
+    ObjectCannotBeMapped { index: u16, subindex: u8 },
+    #[fail(
+        display = "value for object {:#06x},{:#04x} is not a {:?}",
+        index, subindex, data_type
+    )]
+    MismatchingValue {
+        index: u16,
+        subindex: u8,
+        data_type: DataType,
+    },
+    #[fail(
+        display = "value for object {:#06x},{:#04x} does not fit into {} bits",
+        index, subindex, length
+    )]
+    ValueOutOfRange {
+        index: u16,
+        subindex: u8,
+        length: u8,
+    },
+    #[fail(display = "{:#010x} is not an 11 bit PDO COB-ID parameter", value)]
+    InvalidPdoCobId { value: u32 },
+}
+
+const PDO_INVALID_BIT: u32 = 1 << 31;
+const PDO_NO_RTR_BIT: u32 = 1 << 30;
+const PDO_EXTENDED_BIT: u32 = 1 << 29;
+
+/// COB-ID entry (subindex 1) of a PDO communication parameter (0x1400.., 0x1800..)
+///
+/// Bit 31 set marks the PDO invalid (disabled), bit 30 set forbids RTR.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PdoCobId {
+    pub valid: bool,
+    pub rtr_allowed: bool,
+    pub cob_id: u32,
+}
+
+impl TryFrom<u32> for PdoCobId {
+    type Error = PdoError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        let cob_id = value & 0x7FF;
+        if value & PDO_EXTENDED_BIT != 0 || value & !(PDO_INVALID_BIT | PDO_NO_RTR_BIT) != cob_id {
+            return Err(PdoError::InvalidPdoCobId { value });
+        }
+        Ok(PdoCobId {
+            valid: value & PDO_INVALID_BIT == 0,
+            rtr_allowed: value & PDO_NO_RTR_BIT == 0,
+            cob_id,
+        })
+    }
+}
+
+impl From<PdoCobId> for u32 {
+    fn from(pdo_cob_id: PdoCobId) -> u32 {
+        let mut value = pdo_cob_id.cob_id;
+        if !pdo_cob_id.valid {
+            value |= PDO_INVALID_BIT;
+        }
+        if !pdo_cob_id.rtr_allowed {
+            value |= PDO_NO_RTR_BIT;
+        }
+        value
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PdoMappingEntry {
+    pub index: u16,
+    pub subindex: u8,
+    pub data_type: DataType,
+    /// Number of bits the object occupies in the payload
+    pub length: u8,
+}
+
+/// Objects mapped into a PDO in the order they appear in the payload
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PdoMapping {
+    pub entries: Vec<PdoMappingEntry>,
+}
+
+impl PdoMapping {
+    pub fn new() -> Self {
+        PdoMapping::default()
+    }
+
+    /// Map an object with the full size of its data type
+    pub fn map(self, index: u16, subindex: u8, data_type: DataType) -> Self {
+        let length = data_type.bit_size().unwrap_or(0) as u8;
+        self.map_bits(index, subindex, data_type, length)
+    }
+
+    /// Map an object occupying only `length` bits (e.g. a 4 bit value)
+    pub fn map_bits(mut self, index: u16, subindex: u8, data_type: DataType, length: u8) -> Self {
+        self.entries.push(PdoMappingEntry {
+            index,
+            subindex,
+            data_type,
+            length,
+        });
+        self
+    }
+}
+
+fn is_pdo(frame_type: FrameType) -> bool {
+    matches!(
+        frame_type,
+        FrameType::Tpdo1
+            | FrameType::Tpdo2
+            | FrameType::Tpdo3
+            | FrameType::Tpdo4
+            | FrameType::Rpdo1
+            | FrameType::Rpdo2
+            | FrameType::Rpdo3
+            | FrameType::Rpdo4
+    )
+}
+
+fn is_tpdo(frame_type: FrameType) -> bool {
+    matches!(
+        frame_type,
+        FrameType::Tpdo1 | FrameType::Tpdo2 | FrameType::Tpdo3 | FrameType::Tpdo4
+    )
+}
+
+fn is_signed(data_type: DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::INTEGER8
+            | DataType::INTEGER16
+            | DataType::INTEGER24
+            | DataType::INTEGER32
+            | DataType::INTEGER40
+            | DataType::INTEGER48
+            | DataType::INTEGER56
+            | DataType::INTEGER64
+    )
+}
+
+fn bit_mask(length: usize) -> u64 {
+    match length {
+        0 => 0,
+        64.. => u64::MAX,
+        _ => (1 << length) - 1,
+    }
+}
+
+// two's complement value of the lowest bits of raw, as 64 bits
+fn sign_extend(raw: u64, bits: usize) -> u64 {
+    if bits > 0 && bits < 64 && raw >> (bits - 1) & 1 == 1 {
+        raw | !bit_mask(bits)
+    } else {
+        raw
+    }
+}
+
+// size of the data type in bits if an entry of that length fits into it
+fn type_bits(entry: &PdoMappingEntry) -> Option<usize> {
+    match entry.data_type.bit_size() {
+        Some(bits) if entry.length > 0 && entry.length as usize <= bits => Some(bits),
+        _ => None,
+    }
+}
+
+/// Slice the payload of a PDO frame into the typed values of its mapping.
+///
+/// Objects are packed least significant bit first at bit granularity.
+/// Decoding stops at the first mapped object that is not fully contained
+/// in the payload or has no fixed size.
+pub fn decode_pdo(frame: &CANOpenFrame, mapping: &PdoMapping) -> Vec<(u16, u8, Data)> {
+    let mut values = vec![];
+    if !is_pdo(frame.frame_type()) {
+        return values;
+    }
+    let payload = LittleEndian::read_u64(&frame.data());
+    let payload_bits = frame.length() as usize * 8;
+    let mut offset = 0;
+    for entry in mapping.entries.iter() {
+        let length = entry.length as usize;
+        let bits = match type_bits(entry) {
+            Some(bits) => bits,
+            None => break,
+        };
+        if offset + length > payload_bits {
+            break;
+        }
+        let mut raw = (payload >> offset) & bit_mask(length);
+        if is_signed(entry.data_type) {
+            raw = sign_extend(raw, length);
+        }
+        let bytes = raw.to_le_bytes();
+        match Data::from_le_bytes(&bytes[..bits.div_ceil(8)], entry.data_type) {
+            Ok(value) => values.push((entry.index, entry.subindex, value)),
+            Err(_) => break,
+        }
+        offset += length;
+    }
+    values
+}
+
+/// Pack values into a PDO payload according to the mapping,
+/// the inverse of `decode_pdo`.
+pub fn encode_pdo(mapping: &PdoMapping, values: &[Data]) -> Result<Vec<u8>, Error> {
+    if values.len() != mapping.entries.len() {
+        return Err(PdoError::ValueCountMismatch {
+            values: values.len(),
+            entries: mapping.entries.len(),
+        }
+        .into());
+    }
+    let bits: usize = mapping.entries.iter().map(|e| e.length as usize).sum();
+    if bits > 64 {
+        return Err(PdoError::PdoOverflow { bits }.into());
+    }
+    let mut payload = 0_u64;
+    let mut offset = 0;
+    for (entry, value) in mapping.entries.iter().zip(values.iter()) {
+        if type_bits(entry).is_none() {
+            return Err(PdoError::ObjectCannotBeMapped {
+                index: entry.index,
+                subindex: entry.subindex,
+            }
+            .into());
+        }
+        // mapped types have a fixed size, so a matching value is 1..=8 bytes
+        if DataType::from(value.clone()) != entry.data_type {
+            return Err(PdoError::MismatchingValue {
+                index: entry.index,
+                subindex: entry.subindex,
+                data_type: entry.data_type,
+            }
+            .into());
+        }
+        let bytes = Vec::<u8>::try_from(value.clone())?;
+        let raw = LittleEndian::read_uint(&bytes, bytes.len().clamp(1, 8));
+        let length = entry.length as usize;
+        let fits = if is_signed(entry.data_type) {
+            sign_extend(raw & bit_mask(length), length) == sign_extend(raw, bytes.len() * 8)
+        } else {
+            raw & !bit_mask(length) == 0
+        };
+        if !fits {
+            return Err(PdoError::ValueOutOfRange {
+                index: entry.index,
+                subindex: entry.subindex,
+                length: entry.length,
+            }
+            .into());
+        }
+        payload |= (raw & bit_mask(length)) << offset;
+        offset += entry.length as usize;
+    }
+    Ok(payload.to_le_bytes()[..bits.div_ceil(8)].to_vec())
+}
+
+/// Frame to publish (data) or request (remote) a PDO with,
+/// only TPDOs can be requested
+pub fn pdo_frame(cob_id: u32, data: &[u8], is_rtr: bool) -> CANOpenFrameResult {
+    let (frame_type, _) = FrameType::from_cob_id(cob_id)?;
+    if !is_pdo(frame_type) {
+        return Err(PdoError::NotAPdo { cob_id }.into());
+    }
+    if is_rtr && !is_tpdo(frame_type) {
+        return Err(PdoError::NotATpdo { cob_id }.into());
+    }
+    CANOpenFrame::new_with_rtr(cob_id, data, is_rtr)
+}
+
+/// Send process data without assembling frames by hand
+pub struct PdoPublisher<T: CanInterface> {
+    can_interface: T,
+}
+
+impl<T: CanInterface> PdoPublisher<T> {
+    pub fn new(can_interface: T) -> Self {
+        PdoPublisher { can_interface }
+    }
+
+    /// Send a PDO with up to 8 bytes of data
+    pub async fn publish(&mut self, cob_id: u32, data: &[u8]) -> Result<(), Error> {
+        let frame = pdo_frame(cob_id, data, false)?;
+        self.can_interface.send(frame).await
+    }
+
+    /// Ask the producer of a remotely requestable TPDO to transmit it
+    pub async fn request(&mut self, cob_id: u32) -> Result<(), Error> {
+        let frame = pdo_frame(cob_id, &[], true)?;
+        self.can_interface.send(frame).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_pdo() {
+        let mapping = PdoMapping::new()
+            .map(0x6041, 0x00, DataType::UNSIGNED16)
+            .map(0x6061, 0x00, DataType::INTEGER8)
+            .map(0x6064, 0x00, DataType::INTEGER32);
+        let frame = CANOpenFrame::new(0x1A5, &[0x37, 0x02, 0xFF, 0x00, 0x00, 0x01, 0x80]).unwrap();
+        assert_eq!(
+            vec![
+                (0x6041, 0x00, Data::UNSIGNED16(0x0237)),
+                (0x6061, 0x00, Data::INTEGER8(-1)),
+                (0x6064, 0x00, Data::INTEGER32(0x8001_0000_u32 as i32)),
+            ],
+            decode_pdo(&frame, &mapping)
+        );
+    }
+
+    #[test]
+    fn test_decode_pdo_short_payload() {
+        let mapping = PdoMapping::new()
+            .map(0x2000, 0x01, DataType::UNSIGNED8)
+            .map(0x2000, 0x02, DataType::UNSIGNED32);
+        let frame = CANOpenFrame::new(0x225, &[0x11, 0x22, 0x33]).unwrap();
+        assert_eq!(
+            vec![(0x2000, 0x01, Data::UNSIGNED8(0x11))],
+            decode_pdo(&frame, &mapping)
+        );
+
+        let sdo = CANOpenFrame::new(0x625, &[0x11, 0x22, 0x33]).unwrap();
+        assert!(decode_pdo(&sdo, &mapping).is_empty());
+    }
+
+    fn nibble_mapping() -> PdoMapping {
+        // byte 0: bits 0..3 low nibble, bits 4..7 signed high nibble
+        // byte 1: two booleans in bit 0 and bit 1, then a 6 bit value
+        PdoMapping::new()
+            .map_bits(0x2000, 0x01, DataType::UNSIGNED8, 4)
+            .map_bits(0x2000, 0x02, DataType::INTEGER8, 4)
+            .map(0x2001, 0x01, DataType::BOOLEAN)
+            .map(0x2001, 0x02, DataType::BOOLEAN)
+            .map_bits(0x2002, 0x00, DataType::UNSIGNED16, 6)
+    }
+
+    #[test]
+    fn test_decode_pdo_bits() {
+        // 0xA5: low nibble 5, high nibble 0b1010 = -6
+        // 0xB6: 0b1011_0110 -> false, true, 0b101101 = 45
+        let frame = CANOpenFrame::new(0x1A5, &[0xA5, 0xB6]).unwrap();
+        assert_eq!(
+            vec![
+                (0x2000, 0x01, Data::UNSIGNED8(5)),
+                (0x2000, 0x02, Data::INTEGER8(-6)),
+                (0x2001, 0x01, Data::BOOLEAN(false)),
+                (0x2001, 0x02, Data::BOOLEAN(true)),
+                (0x2002, 0x00, Data::UNSIGNED16(45)),
+            ],
+            decode_pdo(&frame, &nibble_mapping())
+        );
+    }
+
+    #[test]
+    fn test_encode_pdo_bits() {
+        let values = [
+            Data::UNSIGNED8(5),
+            Data::INTEGER8(-6),
+            Data::BOOLEAN(false),
+            Data::BOOLEAN(true),
+            Data::UNSIGNED16(45),
+        ];
+        assert_eq!(
+            vec![0xA5, 0xB6],
+            encode_pdo(&nibble_mapping(), &values).unwrap()
+        );
+
+        let mapping = PdoMapping::new()
+            .map(0x6041, 0x00, DataType::UNSIGNED16)
+            .map(0x6064, 0x00, DataType::INTEGER32);
+        assert_eq!(
+            vec![0x37, 0x02, 0xFE, 0xFF, 0xFF, 0xFF],
+            encode_pdo(&mapping, &[Data::UNSIGNED16(0x0237), Data::INTEGER32(-2)]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_encode_pdo_mismatching_values() {
+        let mapping = PdoMapping::new().map(0x2000, 0x01, DataType::UNSIGNED8);
+        assert!(encode_pdo(&mapping, &[Data::NIL]).is_err());
+        assert!(encode_pdo(&mapping, &[Data::VISIBLESTRING(vec![])]).is_err());
+        assert!(encode_pdo(&mapping, &[Data::UNSIGNED32(0x1234)]).is_err());
+        assert!(encode_pdo(&mapping, &[Data::REAL32(1.0)]).is_err());
+        assert_eq!(
+            vec![0x12],
+            encode_pdo(&mapping, &[Data::UNSIGNED8(0x12)]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_encode_pdo_value_range() {
+        let mapping = PdoMapping::new()
+            .map_bits(0x2000, 0x01, DataType::UNSIGNED8, 4)
+            .map_bits(0x2000, 0x02, DataType::INTEGER8, 4);
+        let encode = |unsigned, signed| {
+            encode_pdo(
+                &mapping,
+                &[Data::UNSIGNED8(unsigned), Data::INTEGER8(signed)],
+            )
+        };
+        assert_eq!(vec![0x8F], encode(0x0F, -8).unwrap());
+        assert_eq!(vec![0x70], encode(0x00, 7).unwrap());
+        // unsigned value wider than the slot
+        assert!(encode(0x1F, 0).is_err());
+        // signed values outside -8..=7
+        assert!(encode(0x00, 8).is_err());
+        assert!(encode(0x00, -9).is_err());
+    }
+
+    #[test]
+    fn test_pdo_frame_validation() {
+        let frame = pdo_frame(0x201, &[1, 2, 3], false).unwrap();
+        assert_eq!(FrameType::Rpdo1, frame.frame_type());
+        assert!(pdo_frame(0x201, &[0; 9], false).is_err());
+        assert!(pdo_frame(0x601, &[1], false).is_err());
+        assert!(pdo_frame(0x701, &[1], false).is_err());
+
+        let request = pdo_frame(0x181, &[], true).unwrap();
+        assert!(request.is_rtr());
+        assert!(pdo_frame(0x201, &[], true).is_err());
+    }
+
+    // records sent frames instead of using a bus
+    struct MockInterface(Vec<CANOpenFrame>);
+
+    #[async_trait::async_trait]
+    impl CanInterface for MockInterface {
+        async fn send(&mut self, frame: CANOpenFrame) -> Result<(), Error> {
+            self.0.push(frame);
+            Ok(())
+        }
+
+        async fn recv(&mut self) -> Option<Result<CANOpenFrame, Error>> {
+            None
+        }
+    }
+
+    #[test]
+    fn test_pdo_publisher() {
+        let mut publisher = PdoPublisher::new(MockInterface(vec![]));
+        futures::executor::block_on(async {
+            publisher.publish(0x201, &[1, 2]).await.unwrap();
+            publisher.request(0x181).await.unwrap();
+            assert!(publisher.request(0x201).await.is_err());
+        });
+        let sent = &publisher.can_interface.0;
+        assert_eq!(2, sent.len());
+        assert_eq!(CANOpenFrame::new(0x201, &[1, 2]).unwrap(), sent[0]);
+        assert!(sent[1].is_rtr());
+    }
+
+    #[test]
+    fn test_pdo_cob_id() {
+        let disabled = PdoCobId::try_from(0x8000_0201).unwrap();
+        assert_eq!(
+            PdoCobId {
+                valid: false,
+                rtr_allowed: true,
+                cob_id: 0x201
+            },
+            disabled
+        );
+        assert_eq!(0x8000_0201, u32::from(disabled));
+        let no_rtr = PdoCobId::try_from(0x4000_0181).unwrap();
+        assert!(no_rtr.valid && !no_rtr.rtr_allowed);
+        assert_eq!(0x4000_0181, u32::from(no_rtr));
+        assert!(PdoCobId::try_from(0x2000_0181).is_err());
+        assert!(PdoCobId::try_from(0x0000_0881).is_err());
+    }
+
+    #[test]
+    fn test_encode_pdo_errors() {
+        let mapping = PdoMapping::new()
+            .map(0x2000, 0x01, DataType::UNSIGNED64)
+            .map(0x2000, 0x02, DataType::UNSIGNED8);
+        let values = [Data::UNSIGNED64(0), Data::UNSIGNED8(0)];
+        assert!(encode_pdo(&mapping, &values).is_err());
+        assert!(encode_pdo(&nibble_mapping(), &values).is_err());
+    }
+}
@@ -0,0 +1,77 @@
+use std::fmt;
+
+/// Index of the device type object, its low 16 bits hold the profile number
+pub const DEVICE_TYPE_INDEX: u16 = 0x1000;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum DeviceProfile {
+    GenericIo,
+    Drive,
+    MeasuringDevice,
+    Encoder,
+    Unknown(u16),
+}
+
+impl DeviceProfile {
+    /// Extract the profile from the value of object 0x1000,0x00
+    pub fn from_device_type(device_type: u32) -> Self {
+        (device_type as u16).into()
+    }
+
+    pub fn number(&self) -> u16 {
+        match self {
+            DeviceProfile::GenericIo => 401,
+            DeviceProfile::Drive => 402,
+            DeviceProfile::MeasuringDevice => 404,
+            DeviceProfile::Encoder => 406,
+            DeviceProfile::Unknown(number) => *number,
+        }
+    }
+}
+
+impl From<u16> for DeviceProfile {
+    fn from(number: u16) -> Self {
+        match number {
+            401 => DeviceProfile::GenericIo,
+            402 => DeviceProfile::Drive,
+            404 => DeviceProfile::MeasuringDevice,
+            406 => DeviceProfile::Encoder,
+            number => DeviceProfile::Unknown(number),
+        }
+    }
+}
+
+impl fmt::Display for DeviceProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DeviceProfile::GenericIo => "generic I/O modules",
+            DeviceProfile::Drive => "drives and motion control",
+            DeviceProfile::MeasuringDevice => "measuring devices and closed-loop controllers",
+            DeviceProfile::Encoder => "encoders",
+            DeviceProfile::Unknown(_) => "unknown profile",
+        };
+        write!(f, "CiA {} ({})", self.number(), name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_device_type() {
+        // additional information in the high 16 bits is ignored
+        assert_eq!(
+            DeviceProfile::Drive,
+            DeviceProfile::from_device_type(0x0002_0192)
+        );
+        assert_eq!(
+            DeviceProfile::Unknown(0),
+            DeviceProfile::from_device_type(0)
+        );
+        assert_eq!(
+            "CiA 406 (encoders)",
+            DeviceProfile::from_device_type(406).to_string()
+        );
+    }
+}
@@ -1,3 +1,5 @@
+#![allow(non_local_definitions)]
+
 use super::*;
 use failure::{Error, Fail};
 use std::fmt;
@@ -121,6 +123,44 @@ impl From<u32> for SDOAbortCode {
     }
 }
 
+// an unknown abort code is sent as general error
+impl From<&SDOAbortCode> for u32 {
+    fn from(abort_code: &SDOAbortCode) -> u32 {
+        match abort_code {
+            SDOAbortCode::ToggleBitNotAlternated => 0x0503_0000,
+            SDOAbortCode::SDOProtocolTimedOut => 0x0504_0000,
+            SDOAbortCode::CommandSpecifierError => 0x0504_0001,
+            SDOAbortCode::InvalidBlockSize => 0x0504_0002,
+            SDOAbortCode::InvalidSequenceNumber => 0x0504_0003,
+            SDOAbortCode::CRCError => 0x0504_0004,
+            SDOAbortCode::OutOfMemory => 0x0504_0005,
+            SDOAbortCode::UnsupportedAccess => 0x0601_0000,
+            SDOAbortCode::ReadWriteOnlyError => 0x0601_0001,
+            SDOAbortCode::WriteReadOnlyError => 0x0601_0002,
+            SDOAbortCode::ObjectDoesNotExist => 0x0602_0000,
+            SDOAbortCode::ObjectCannotBeMapped => 0x0604_0041,
+            SDOAbortCode::PDOOverflow => 0x0604_0042,
+            SDOAbortCode::ParameterIncompatibility => 0x0604_0043,
+            SDOAbortCode::InternalIncompatibility => 0x0604_0047,
+            SDOAbortCode::HardwareError => 0x0606_0000,
+            SDOAbortCode::WrongLength => 0x0607_0010,
+            SDOAbortCode::TooLong => 0x0607_0012,
+            SDOAbortCode::TooShort => 0x0607_0013,
+            SDOAbortCode::SubindexDoesNotExist => 0x0609_0011,
+            SDOAbortCode::WrongValue => 0x0609_0030,
+            SDOAbortCode::ValueTooHigh => 0x0609_0031,
+            SDOAbortCode::ValueTooLow => 0x0609_0032,
+            SDOAbortCode::RangeError => 0x0609_0036,
+            SDOAbortCode::GeneralError => 0x0800_0000,
+            SDOAbortCode::StorageError => 0x0800_0020,
+            SDOAbortCode::LocalControlError => 0x0800_0021,
+            SDOAbortCode::DeviceStateError => 0x0800_0022,
+            SDOAbortCode::DictionaryError => 0x0800_0023,
+            SDOAbortCode::UnknownAbortCode => 0x0800_0000,
+        }
+    }
+}
+
 impl From<u8> for SDOResult {
     fn from(data: u8) -> SDOResult {
         match data {
@@ -176,6 +216,18 @@ impl SDOServerResponse {
             _ => Err(SDOError::new("SDO frame parse error").into()),
         }
     }
+
+    /// Confirm the response acknowledges a download,
+    /// an abort is returned as its abort code
+    pub fn download_acknowledged(&self) -> Result<()> {
+        match self.result {
+            SDOResult::Success => Ok(()),
+            SDOResult::Failure => Err(SDOAbortCode::from(self.data).into()),
+            SDOResult::UnknownResult(_) => {
+                Err(SDOError::new("SDO response is not a download acknowledgment").into())
+            }
+        }
+    }
 }
 
 impl std::fmt::Display for SDOServerResponse {
@@ -199,6 +251,185 @@ impl std::fmt::Display for SDOServerResponse {
     }
 }
 
+/// Command specifier (client or server) in the top three bits of the command byte
+pub const CCS_MASK: u8 = 0b1110_0000;
+/// Shift to get the command specifier from the command byte
+pub const CCS_SHIFT: u8 = 5;
+/// Toggle bit of segment frames
+pub const TOGGLE_FLAG: u8 = 0b0001_0000;
+/// Initiate frames: number of bytes not containing data (expedited and size indicated)
+pub const UNUSED_BYTES_MASK: u8 = 0b0000_1100;
+/// Segment frames: number of bytes not containing data
+pub const SEGMENT_UNUSED_BYTES_MASK: u8 = 0b0000_1110;
+/// Initiate frames: data is transferred expedited in this frame
+pub const EXPEDITED_FLAG: u8 = 0b0000_0010;
+/// Initiate frames: the data size is indicated
+pub const SIZE_FLAG: u8 = 0b0000_0001;
+
+/// Sender of an SDO frame, the command specifiers depend on it
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SdoRole {
+    Client,
+    Server,
+}
+
+impl SdoRole {
+    /// Sender of SDO frames of this type, `None` for non SDO frames
+    pub fn from_frame_type(frame_type: FrameType) -> Option<Self> {
+        match frame_type {
+            FrameType::SsdoRx => Some(SdoRole::Client),
+            FrameType::SsdoTx => Some(SdoRole::Server),
+            _ => None,
+        }
+    }
+
+    pub fn opposite(&self) -> Self {
+        match self {
+            SdoRole::Client => SdoRole::Server,
+            SdoRole::Server => SdoRole::Client,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CommandSpecifier {
+    InitiateDownload,
+    DownloadSegment,
+    InitiateUpload,
+    UploadSegment,
+    Abort,
+    BlockDownload,
+    BlockUpload,
+    Unknown(u8),
+}
+
+impl fmt::Display for CommandSpecifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandSpecifier::InitiateDownload => write!(f, "Download"),
+            CommandSpecifier::DownloadSegment => write!(f, "Download segment"),
+            CommandSpecifier::InitiateUpload => write!(f, "Upload"),
+            CommandSpecifier::UploadSegment => write!(f, "Upload segment"),
+            CommandSpecifier::Abort => write!(f, "Abort"),
+            CommandSpecifier::BlockDownload => write!(f, "Block download"),
+            CommandSpecifier::BlockUpload => write!(f, "Block upload"),
+            CommandSpecifier::Unknown(specifier) => write!(f, "Unknown ({})", specifier),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CommandByteInfo {
+    pub specifier: CommandSpecifier,
+    pub expedited: bool,
+    pub size_indicated: bool,
+    /// Number of data bytes in the frame, if given by the command byte
+    pub data_size: Option<u8>,
+    pub toggle: Option<bool>,
+}
+
+impl fmt::Display for CommandByteInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.specifier)?;
+        if self.expedited {
+            write!(f, ", expedited")?;
+        }
+        match self.data_size {
+            Some(size) => write!(f, ", size={}", size)?,
+            None if self.size_indicated => write!(f, ", size indicated")?,
+            None => {}
+        }
+        if let Some(toggle) = self.toggle {
+            write!(f, ", toggle={}", toggle as u8)?;
+        }
+        Ok(())
+    }
+}
+
+/// Explain the command byte (first data byte) of an SDO frame
+pub fn decode_command_byte(byte: u8, role: SdoRole) -> CommandByteInfo {
+    let specifier = match (role, (byte & CCS_MASK) >> CCS_SHIFT) {
+        (SdoRole::Client, 0) | (SdoRole::Server, 1) => CommandSpecifier::DownloadSegment,
+        (SdoRole::Client, 1) | (SdoRole::Server, 3) => CommandSpecifier::InitiateDownload,
+        (SdoRole::Client, 2) | (SdoRole::Server, 2) => CommandSpecifier::InitiateUpload,
+        (SdoRole::Client, 3) | (SdoRole::Server, 0) => CommandSpecifier::UploadSegment,
+        (_, 4) => CommandSpecifier::Abort,
+        (SdoRole::Client, 5) | (SdoRole::Server, 6) => CommandSpecifier::BlockUpload,
+        (SdoRole::Client, 6) | (SdoRole::Server, 5) => CommandSpecifier::BlockDownload,
+        (_, specifier) => CommandSpecifier::Unknown(specifier),
+    };
+    let mut info = CommandByteInfo {
+        specifier,
+        expedited: false,
+        size_indicated: false,
+        data_size: None,
+        toggle: None,
+    };
+    match (role, specifier) {
+        // initiate frames carrying data
+        (SdoRole::Client, CommandSpecifier::InitiateDownload)
+        | (SdoRole::Server, CommandSpecifier::InitiateUpload) => {
+            info.expedited = byte & EXPEDITED_FLAG != 0;
+            info.size_indicated = byte & SIZE_FLAG != 0;
+            if info.expedited && info.size_indicated {
+                info.data_size = Some(4 - ((byte & UNUSED_BYTES_MASK) >> 2));
+            }
+        }
+        // segments carrying data
+        (SdoRole::Client, CommandSpecifier::DownloadSegment)
+        | (SdoRole::Server, CommandSpecifier::UploadSegment) => {
+            info.size_indicated = true;
+            info.data_size = Some(7 - ((byte & SEGMENT_UNUSED_BYTES_MASK) >> 1));
+            info.toggle = Some(byte & TOGGLE_FLAG != 0);
+        }
+        // segment request and acknowledgment
+        (SdoRole::Client, CommandSpecifier::UploadSegment)
+        | (SdoRole::Server, CommandSpecifier::DownloadSegment) => {
+            info.toggle = Some(byte & TOGGLE_FLAG != 0);
+        }
+        _ => {}
+    }
+    info
+}
+
+/// Abort frame sent by the SDO client (request COB-ID 0x600 + node)
+pub fn sdo_client_abort_frame(
+    id: NodeId,
+    index: u16,
+    subindex: u8,
+    abort_code: &SDOAbortCode,
+) -> CANOpenFrameResult {
+    sdo_abort_frame(id, 0x600, index, subindex, abort_code.into())
+}
+
+/// Abort frame sent by the SDO server (response COB-ID 0x580 + node)
+pub fn sdo_server_abort_frame(
+    id: NodeId,
+    index: u16,
+    subindex: u8,
+    abort_code: &SDOAbortCode,
+) -> CANOpenFrameResult {
+    sdo_abort_frame(id, 0x580, index, subindex, abort_code.into())
+}
+
+/// Both interpretations of a command byte, client first, e.g. for captures
+/// whose direction is not trustworthy
+pub fn decode_command_byte_both_roles(byte: u8) -> (CommandByteInfo, CommandByteInfo) {
+    (
+        decode_command_byte(byte, SdoRole::Client),
+        decode_command_byte(byte, SdoRole::Server),
+    )
+}
+
+/// Explain the command byte of an SDO frame in the role given by its frame type
+pub fn decode_sdo_frame_command(frame: &CANOpenFrame) -> Option<CommandByteInfo> {
+    let role = SdoRole::from_frame_type(frame.frame_type())?;
+    if frame.length() == 0 {
+        return None;
+    }
+    Some(decode_command_byte(frame.data()[0], role))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -206,4 +437,104 @@ mod tests {
     #[ignore]
     #[test]
     fn main() {}
+
+    #[test]
+    fn test_download_acknowledged() {
+        let response =
+            |frame: CANOpenFrameResult| SDOServerResponse::parse(&frame.unwrap()).unwrap();
+
+        let ack = response(successful_download_acknowledgment_frame(
+            node(0x05),
+            0x580,
+            0x1017,
+            0x00,
+        ));
+        assert!(ack.download_acknowledged().is_ok());
+
+        let abort = response(sdo_abort_frame(
+            node(0x05),
+            0x580,
+            0x1017,
+            0x00,
+            0x0601_0002,
+        ));
+        let error = abort.download_acknowledged().unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<SDOAbortCode>(),
+            Some(SDOAbortCode::WriteReadOnlyError)
+        ));
+
+        let upload = response(upload_1_byte_frame(node(0x05), 0x580, 0x1017, 0x00, 0x01));
+        assert!(upload.download_acknowledged().is_err());
+    }
+
+    #[test]
+    fn test_decode_command_byte() {
+        let decode = |byte, role| decode_command_byte(byte, role).to_string();
+        assert_eq!("Download, expedited, size=2", decode(0x2B, SdoRole::Client));
+        assert_eq!("Download, size indicated", decode(0x21, SdoRole::Client));
+        assert_eq!("Download", decode(0x60, SdoRole::Server));
+        assert_eq!("Upload", decode(0x40, SdoRole::Client));
+        assert_eq!("Upload, expedited, size=4", decode(0x43, SdoRole::Server));
+        assert_eq!("Upload segment, toggle=0", decode(0x60, SdoRole::Client));
+        assert_eq!(
+            "Upload segment, size=7, toggle=1",
+            decode(0x10, SdoRole::Server)
+        );
+        assert_eq!(
+            "Download segment, size=3, toggle=0",
+            decode(0x09, SdoRole::Client)
+        );
+        assert_eq!("Abort", decode(0x80, SdoRole::Server));
+        assert_eq!("Unknown (7)", decode(0xE0, SdoRole::Client));
+    }
+
+    #[test]
+    fn test_abort_frames() {
+        let frame =
+            sdo_client_abort_frame(node(0x05), 0x1008, 0x00, &SDOAbortCode::SDOProtocolTimedOut)
+                .unwrap();
+        assert_eq!(0x605, frame.cob_id());
+        assert_eq!(
+            [0x80, 0x08, 0x10, 0x00, 0x00, 0x00, 0x04, 0x05],
+            frame.data()
+        );
+        assert_eq!(
+            Some(SdoRole::Client),
+            SdoRole::from_frame_type(frame.frame_type())
+        );
+
+        let frame =
+            sdo_server_abort_frame(node(0x05), 0x1008, 0x00, &SDOAbortCode::ObjectDoesNotExist)
+                .unwrap();
+        assert_eq!(0x585, frame.cob_id());
+        let response = SDOServerResponse::parse(&frame).unwrap();
+        assert!(matches!(response.result, SDOResult::Failure));
+        assert_eq!(0x0602_0000, response.data);
+
+        for code in [0x0503_0000, 0x0609_0011, 0x0800_0023] {
+            assert_eq!(code, u32::from(&SDOAbortCode::from(code)));
+        }
+    }
+
+    #[test]
+    fn test_decode_command_byte_roles() {
+        let (client, server) = decode_command_byte_both_roles(0x60);
+        assert_eq!(CommandSpecifier::UploadSegment, client.specifier);
+        assert_eq!(CommandSpecifier::InitiateDownload, server.specifier);
+        assert_eq!(SdoRole::Server, SdoRole::Client.opposite());
+
+        let request = upload_request_frame(node(0x05), 0x600, 0x1000, 0x00).unwrap();
+        assert_eq!(
+            CommandSpecifier::InitiateUpload,
+            decode_sdo_frame_command(&request).unwrap().specifier
+        );
+        let ack =
+            successful_download_acknowledgment_frame(node(0x05), 0x580, 0x1000, 0x00).unwrap();
+        assert_eq!(
+            CommandSpecifier::InitiateDownload,
+            decode_sdo_frame_command(&ack).unwrap().specifier
+        );
+        assert!(decode_sdo_frame_command(&sync_frame().unwrap()).is_none());
+    }
 }
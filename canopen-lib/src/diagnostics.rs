@@ -0,0 +1,159 @@
+//! Heuristics to find misconfigured nodes during bring-up.
+
+use std::collections::BTreeMap;
+
+use crate::frame::{CANOpenFrame, FrameType};
+#[cfg(feature = "socketcan")]
+use crate::interface::CanInterface;
+
+#[derive(Default)]
+struct NodeActivity {
+    boot_ups: usize,
+    states: Vec<u8>,
+    responses_since_request: usize,
+    duplicate_response: bool,
+}
+
+impl NodeActivity {
+    // the heartbeat state returned to the one before the previous at least twice,
+    // as two nodes in different states interleaving their heartbeats would do
+    fn oscillates(&self) -> bool {
+        self.states.windows(3).filter(|w| w[0] == w[2]).count() >= 2
+    }
+
+    // a commanded state change or reset explains the following boot-up and states
+    fn forget_states(&mut self) {
+        self.boot_ups = 0;
+        self.states.clear();
+    }
+
+    fn is_suspect(&self) -> bool {
+        self.boot_ups > 1 || self.duplicate_response || self.oscillates()
+    }
+}
+
+/// Node ids whose traffic looks like more than one node is using them:
+/// several boot-ups, heartbeat states alternating back and forth or
+/// several SDO responses to one request (block transfers are not considered).
+/// Boot-ups and state changes following an NMT command to the node are expected.
+pub fn duplicate_node_suspects(frames: &[CANOpenFrame]) -> Vec<u8> {
+    let mut nodes: BTreeMap<u8, NodeActivity> = BTreeMap::new();
+    for frame in frames {
+        if frame.frame_type() == FrameType::Nmt {
+            if frame.length() >= 2 {
+                match frame.data()[1] {
+                    0 => nodes.values_mut().for_each(NodeActivity::forget_states),
+                    node_id => nodes.entry(node_id).or_default().forget_states(),
+                }
+            }
+            continue;
+        }
+        let node = nodes.entry(frame.node_id()).or_default();
+        match frame.frame_type() {
+            FrameType::NmtErrorControl if !frame.is_rtr() && frame.length() > 0 => {
+                // without the toggle bit of node guarding responses
+                let state = frame.data()[0] & 0x7F;
+                if state == 0 {
+                    node.boot_ups += 1;
+                } else if node.states.last() != Some(&state) {
+                    node.states.push(state);
+                }
+            }
+            FrameType::SsdoRx => node.responses_since_request = 0,
+            FrameType::SsdoTx => {
+                node.responses_since_request += 1;
+                if node.responses_since_request > 1 {
+                    node.duplicate_response = true;
+                }
+            }
+            _ => {}
+        }
+    }
+    nodes
+        .into_iter()
+        .filter(|(_, activity)| activity.is_suspect())
+        .map(|(node_id, _)| node_id)
+        .collect()
+}
+
+/// Watch the bus for the given duration and report suspected duplicate node ids
+#[cfg(feature = "socketcan")]
+pub async fn detect_duplicate_nodes<T: CanInterface>(
+    can_interface: &mut T,
+    duration: std::time::Duration,
+) -> Vec<u8> {
+    use futures_util::future::{select, Either};
+
+    let mut frames = vec![];
+    let mut window = futures_timer::Delay::new(duration);
+    loop {
+        match select(can_interface.recv(), &mut window).await {
+            Either::Left((Some(Ok(frame)), _)) => frames.push(frame),
+            // not a CANOpen frame
+            Either::Left((Some(Err(_)), _)) => continue,
+            Either::Left((None, _)) | Either::Right(_) => break,
+        }
+    }
+    duplicate_node_suspects(&frames)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::{
+        heartbeat_frame, node, set_mode_frame, successful_download_acknowledgment_frame,
+        upload_request_frame, Mode, State,
+    };
+
+    #[test]
+    fn test_duplicate_node_suspects() {
+        let heartbeat = |id, state| heartbeat_frame(node(id), state).unwrap();
+        let frames = vec![
+            // node 1 boots twice
+            heartbeat(1, State::BootUp),
+            heartbeat(1, State::Operational),
+            heartbeat(1, State::BootUp),
+            // node 2 alternates between two states
+            heartbeat(2, State::Operational),
+            heartbeat(2, State::PreOperational),
+            heartbeat(2, State::Operational),
+            heartbeat(2, State::PreOperational),
+            // node 3 changes state once and answers every request once
+            heartbeat(3, State::BootUp),
+            heartbeat(3, State::PreOperational),
+            heartbeat(3, State::Operational),
+            upload_request_frame(node(3), 0x600, 0x1000, 0).unwrap(),
+            successful_download_acknowledgment_frame(node(3), 0x580, 0x1000, 0).unwrap(),
+            // node 4 answers one request twice
+            upload_request_frame(node(4), 0x600, 0x1000, 0).unwrap(),
+            successful_download_acknowledgment_frame(node(4), 0x580, 0x1000, 0).unwrap(),
+            successful_download_acknowledgment_frame(node(4), 0x580, 0x1000, 0).unwrap(),
+        ];
+        assert_eq!(vec![1, 2, 4], duplicate_node_suspects(&frames));
+    }
+
+    #[test]
+    fn test_commanded_transitions_are_not_suspect() {
+        let heartbeat = |id, state| heartbeat_frame(node(id), state).unwrap();
+        let command = |id, mode| set_mode_frame(node(id), mode).unwrap();
+        let frames = vec![
+            // the master switches node 1 back and forth
+            heartbeat(1, State::BootUp),
+            heartbeat(1, State::PreOperational),
+            command(1, Mode::Operational),
+            heartbeat(1, State::Operational),
+            command(1, Mode::PreOperational),
+            heartbeat(1, State::PreOperational),
+            command(1, Mode::Operational),
+            heartbeat(1, State::Operational),
+            // and resets all nodes twice
+            command(0, Mode::ResetApplication),
+            heartbeat(1, State::BootUp),
+            heartbeat(2, State::BootUp),
+            command(0, Mode::ResetCommunication),
+            heartbeat(1, State::BootUp),
+            heartbeat(2, State::BootUp),
+        ];
+        assert!(duplicate_node_suspects(&frames).is_empty());
+    }
+}
@@ -0,0 +1,187 @@
+use std::fmt;
+use tokio_socketcan::CANFrame;
+
+// error classes encoded in the id of a SocketCAN error frame (linux/can/error.h)
+const CAN_ERR_TX_TIMEOUT: u32 = 0x0001;
+const CAN_ERR_LOSTARB: u32 = 0x0002;
+const CAN_ERR_CRTL: u32 = 0x0004;
+const CAN_ERR_PROT: u32 = 0x0008;
+const CAN_ERR_TRX: u32 = 0x0010;
+const CAN_ERR_ACK: u32 = 0x0020;
+const CAN_ERR_BUSOFF: u32 = 0x0040;
+const CAN_ERR_BUSERROR: u32 = 0x0080;
+const CAN_ERR_RESTARTED: u32 = 0x0100;
+const CAN_ERR_CNT: u32 = 0x0200;
+
+// controller status in data byte 1
+const CAN_ERR_CRTL_RX_OVERFLOW: u8 = 0x01;
+const CAN_ERR_CRTL_TX_OVERFLOW: u8 = 0x02;
+const CAN_ERR_CRTL_RX_WARNING: u8 = 0x04;
+const CAN_ERR_CRTL_TX_WARNING: u8 = 0x08;
+const CAN_ERR_CRTL_RX_PASSIVE: u8 = 0x10;
+const CAN_ERR_CRTL_TX_PASSIVE: u8 = 0x20;
+const CAN_ERR_CRTL_ACTIVE: u8 = 0x40;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CanBusCondition {
+    TransmitTimeout,
+    LostArbitration,
+    RxOverflow,
+    TxOverflow,
+    ErrorWarning,
+    ErrorPassive,
+    ErrorActive,
+    ProtocolViolation,
+    TransceiverError,
+    NoAcknowledge,
+    BusOff,
+    BusError,
+    Restarted,
+}
+
+impl fmt::Display for CanBusCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            CanBusCondition::TransmitTimeout => "tx-timeout",
+            CanBusCondition::LostArbitration => "lost-arbitration",
+            CanBusCondition::RxOverflow => "rx-overflow",
+            CanBusCondition::TxOverflow => "tx-overflow",
+            CanBusCondition::ErrorWarning => "error-warning",
+            CanBusCondition::ErrorPassive => "error-passive",
+            CanBusCondition::ErrorActive => "error-active",
+            CanBusCondition::ProtocolViolation => "protocol-violation",
+            CanBusCondition::TransceiverError => "transceiver-error",
+            CanBusCondition::NoAcknowledge => "no-ack",
+            CanBusCondition::BusOff => "bus-off",
+            CanBusCondition::BusError => "bus-error",
+            CanBusCondition::Restarted => "restarted",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// Bus state reported by a SocketCAN error frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CanBusError {
+    pub conditions: Vec<CanBusCondition>,
+    /// transmit and receive error counters, if reported
+    pub error_counters: Option<(u8, u8)>,
+}
+
+impl CanBusError {
+    pub fn contains(&self, condition: CanBusCondition) -> bool {
+        self.conditions.contains(&condition)
+    }
+}
+
+impl fmt::Display for CanBusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let conditions: Vec<String> = self.conditions.iter().map(|c| c.to_string()).collect();
+        write!(f, "{}", conditions.join(", "))?;
+        if let Some((tx, rx)) = self.error_counters {
+            write!(f, " (tec {} rec {})", tx, rx)?;
+        }
+        Ok(())
+    }
+}
+
+/// Interpret a SocketCAN error frame, `None` for regular frames
+pub fn decode(frame: &CANFrame) -> Option<CanBusError> {
+    if !frame.is_error() {
+        return None;
+    }
+    let class = frame.err();
+    let mut data = [0_u8; 8];
+    data[..frame.data().len()].copy_from_slice(frame.data());
+
+    let mut conditions = vec![];
+    let mut add = |flag: bool, condition| {
+        if flag {
+            conditions.push(condition)
+        }
+    };
+    add(
+        class & CAN_ERR_TX_TIMEOUT != 0,
+        CanBusCondition::TransmitTimeout,
+    );
+    add(
+        class & CAN_ERR_LOSTARB != 0,
+        CanBusCondition::LostArbitration,
+    );
+    if class & CAN_ERR_CRTL != 0 {
+        let status = data[1];
+        add(
+            status & CAN_ERR_CRTL_RX_OVERFLOW != 0,
+            CanBusCondition::RxOverflow,
+        );
+        add(
+            status & CAN_ERR_CRTL_TX_OVERFLOW != 0,
+            CanBusCondition::TxOverflow,
+        );
+        add(
+            status & (CAN_ERR_CRTL_RX_WARNING | CAN_ERR_CRTL_TX_WARNING) != 0,
+            CanBusCondition::ErrorWarning,
+        );
+        add(
+            status & (CAN_ERR_CRTL_RX_PASSIVE | CAN_ERR_CRTL_TX_PASSIVE) != 0,
+            CanBusCondition::ErrorPassive,
+        );
+        add(
+            status & CAN_ERR_CRTL_ACTIVE != 0,
+            CanBusCondition::ErrorActive,
+        );
+    }
+    add(
+        class & CAN_ERR_PROT != 0,
+        CanBusCondition::ProtocolViolation,
+    );
+    add(class & CAN_ERR_TRX != 0, CanBusCondition::TransceiverError);
+    add(class & CAN_ERR_ACK != 0, CanBusCondition::NoAcknowledge);
+    add(class & CAN_ERR_BUSOFF != 0, CanBusCondition::BusOff);
+    add(class & CAN_ERR_BUSERROR != 0, CanBusCondition::BusError);
+    add(class & CAN_ERR_RESTARTED != 0, CanBusCondition::Restarted);
+
+    let error_counters = if class & CAN_ERR_CNT != 0 {
+        Some((data[6], data[7]))
+    } else {
+        None
+    };
+    Some(CanBusError {
+        conditions,
+        error_counters,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_regular_frame_is_no_error() {
+        let frame = CANFrame::new(0x181, &[1, 2], false, false).unwrap();
+        assert_eq!(None, decode(&frame));
+    }
+
+    #[test]
+    fn test_bus_off() {
+        let frame = CANFrame::new(CAN_ERR_BUSOFF, &[0; 8], false, true).unwrap();
+        let error = decode(&frame).unwrap();
+        assert!(error.contains(CanBusCondition::BusOff));
+        assert_eq!("bus-off", error.to_string());
+    }
+
+    #[test]
+    fn test_controller_error_passive_with_counters() {
+        let frame = CANFrame::new(
+            CAN_ERR_CRTL | CAN_ERR_CNT,
+            &[0, CAN_ERR_CRTL_TX_PASSIVE, 0, 0, 0, 0, 128, 5],
+            false,
+            true,
+        )
+        .unwrap();
+        let error = decode(&frame).unwrap();
+        assert_eq!(vec![CanBusCondition::ErrorPassive], error.conditions);
+        assert_eq!(Some((128, 5)), error.error_counters);
+        assert_eq!("error-passive (tec 128 rec 5)", error.to_string());
+    }
+}
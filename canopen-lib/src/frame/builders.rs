@@ -23,7 +23,7 @@ pub fn sync_frame() -> CANOpenFrameResult {
     CANOpenFrame::new(0x080u32, &[])
 }
 
-pub fn set_mode_frame(id: u8, mode: Mode) -> CANOpenFrameResult {
+pub fn set_mode_frame(id: NodeId, mode: Mode) -> CANOpenFrameResult {
     let mode_value = match mode {
         Mode::Operational => 1,
         Mode::Stop => 2,
@@ -32,18 +32,18 @@ pub fn set_mode_frame(id: u8, mode: Mode) -> CANOpenFrameResult {
         Mode::ResetCommunication => 82,
     };
 
-    CANOpenFrame::new(0x000u32, &[mode_value, id])
+    CANOpenFrame::new(0x000u32, &[mode_value, id.into()])
 }
 
 pub fn set_all_mode_frame(mode: Mode) -> CANOpenFrameResult {
-    set_mode_frame(0u8, mode)
+    set_mode_frame(NodeId(0), mode)
 }
 
-pub fn request_mode_frame(id: u8) -> CANOpenFrameResult {
+pub fn request_mode_frame(id: NodeId) -> CANOpenFrameResult {
     CANOpenFrame::new_with_rtr(0x700u32 + u32::from(id), &[], true)
 }
 
-pub fn guarding_frame(id: u8, state: State, toggle: bool) -> CANOpenFrameResult {
+pub fn guarding_frame(id: NodeId, state: State, toggle: bool) -> CANOpenFrameResult {
     let mut state_value = match state {
         State::BootUp => 0x00,
         State::Operational => 0x05,
@@ -59,13 +59,38 @@ pub fn guarding_frame(id: u8, state: State, toggle: bool) -> CANOpenFrameResult
     CANOpenFrame::new(0x700u32 + u32::from(id), &[state_value])
 }
 
-pub fn heartbeat_frame(id: u8, state: State) -> CANOpenFrameResult {
+pub fn heartbeat_frame(id: NodeId, state: State) -> CANOpenFrameResult {
     guarding_frame(id, state, false)
 }
 
+// any SDO frame with index, the command byte is taken as is
+// (e.g. to build deliberately malformed frames in tests)
+pub fn sdo_raw_frame(
+    id: NodeId,
+    address: u32,
+    command: u8,
+    index: u16,
+    subindex: u8,
+    data: [u8; 4],
+) -> CANOpenFrameResult {
+    CANOpenFrame::new(
+        address + u32::from(id),
+        &[
+            command,
+            index.lo(),
+            index.hi(),
+            subindex,
+            data[0],
+            data[1],
+            data[2],
+            data[3],
+        ],
+    )
+}
+
 // sdo client sends updates object on server
 pub fn download_1_byte_frame(
-    id: u8,
+    id: NodeId,
     rx_address: u32,
     index: u16,
     subindex: u8,
@@ -87,7 +112,7 @@ pub fn download_1_byte_frame(
 }
 
 pub fn download_2_bytes_frame(
-    id: u8,
+    id: NodeId,
     rx_address: u32,
     index: u16,
     subindex: u8,
@@ -109,7 +134,7 @@ pub fn download_2_bytes_frame(
 }
 
 pub fn download_3_bytes_frame(
-    id: u8,
+    id: NodeId,
     rx_address: u32,
     index: u16,
     subindex: u8,
@@ -130,8 +155,35 @@ pub fn download_3_bytes_frame(
     )
 }
 
+/// SDO client writes 1 to 4 bytes (expedited download),
+/// more or no data is rejected with `InvalidSdoDataLength`
+pub fn download_frame(
+    id: NodeId,
+    rx_address: u32,
+    index: u16,
+    subindex: u8,
+    data: &[u8],
+) -> CANOpenFrameResult {
+    let length = data.len();
+    if !(1..=4).contains(&length) {
+        return Err(CANOpenFrameError::InvalidSdoDataLength { length }.into());
+    }
+    // expedited, size indicated, number of bytes not containing data
+    let command = 0x23 | ((4 - length as u8) << 2);
+    let mut bytes = [0u8; 4];
+    bytes[..length].copy_from_slice(data);
+    sdo_raw_frame(id, rx_address, command, index, subindex, bytes)
+}
+
+/// SDO client writes four bytes (expedited download)
+///
+/// ```
+/// let frame = col::download_4_bytes_frame(col::NodeId::new(0x05).unwrap(), 0x600, 0x1017, 0x00, [0xE8, 0x03, 0, 0]).unwrap();
+/// assert_eq!(0x605, frame.cob_id());
+/// assert_eq!([0x23, 0x17, 0x10, 0x00, 0xE8, 0x03, 0x00, 0x00], frame.data());
+/// ```
 pub fn download_4_bytes_frame(
-    id: u8,
+    id: NodeId,
     rx_address: u32,
     index: u16,
     subindex: u8,
@@ -153,7 +205,7 @@ pub fn download_4_bytes_frame(
 }
 
 pub fn successful_download_acknowledgment_frame(
-    id: u8,
+    id: NodeId,
     tx_address: u32,
     index: u16,
     subindex: u8,
@@ -173,8 +225,15 @@ pub fn successful_download_acknowledgment_frame(
     )
 }
 
+/// SDO abort transfer, sent by client or server
+///
+/// ```
+/// let frame = col::sdo_abort_frame(col::NodeId::new(0x05).unwrap(), 0x580, 0x1008, 0x00, 0x0602_0000).unwrap();
+/// assert_eq!(0x585, frame.cob_id());
+/// assert_eq!([0x80, 0x08, 0x10, 0x00, 0x00, 0x00, 0x02, 0x06], frame.data());
+/// ```
 pub fn sdo_abort_frame(
-    id: u8,
+    id: NodeId,
     tx_address: u32,
     index: u16,
     subindex: u8,
@@ -183,7 +242,7 @@ pub fn sdo_abort_frame(
     CANOpenFrame::new(
         tx_address + u32::from(id),
         &[
-            0x60,
+            0x80, // command byte
             index.lo(),
             index.hi(),
             subindex,
@@ -195,9 +254,15 @@ pub fn sdo_abort_frame(
     )
 }
 
-// SDO client request data from server
+/// SDO client requests data from server (initiate upload)
+///
+/// ```
+/// let frame = col::upload_request_frame(col::NodeId::new(0x05).unwrap(), 0x600, 0x1008, 0x00).unwrap();
+/// assert_eq!(0x605, frame.cob_id());
+/// assert_eq!([0x40, 0x08, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00], frame.data());
+/// ```
 pub fn upload_request_frame(
-    id: u8,
+    id: NodeId,
     rx_address: u32,
     index: u16,
     subindex: u8,
@@ -217,9 +282,34 @@ pub fn upload_request_frame(
     )
 }
 
+// sdo server responds to an upload request of an object that does not fit
+// into an expedited frame, the data follows in upload segments
+// (not to be confused with the client's upload_request_frame)
+pub fn upload_initiate_segmented_frame(
+    id: NodeId,
+    tx_address: u32,
+    index: u16,
+    subindex: u8,
+    size: u32,
+) -> CANOpenFrameResult {
+    CANOpenFrame::new(
+        tx_address + u32::from(id),
+        &[
+            0x41, // command byte - segmented, size indicated
+            index.lo(),
+            index.hi(),
+            subindex,
+            size.lo().lo(),
+            size.lo().hi(),
+            size.hi().lo(),
+            size.hi().hi(),
+        ],
+    )
+}
+
 // sdo server responds to client
 pub fn upload_1_byte_frame(
-    id: u8,
+    id: NodeId,
     tx_address: u32,
     index: u16,
     subindex: u8,
@@ -242,7 +332,7 @@ pub fn upload_1_byte_frame(
 
 // sdo server responds to client
 pub fn upload_2_bytes_frame(
-    id: u8,
+    id: NodeId,
     tx_address: u32,
     index: u16,
     subindex: u8,
@@ -265,7 +355,7 @@ pub fn upload_2_bytes_frame(
 
 // sdo server responds to client
 pub fn upload_3_bytes_frame(
-    id: u8,
+    id: NodeId,
     tx_address: u32,
     index: u16,
     subindex: u8,
@@ -288,7 +378,7 @@ pub fn upload_3_bytes_frame(
 
 // sdo server responds to client
 pub fn upload_4_bytes_frame(
-    id: u8,
+    id: NodeId,
     tx_address: u32,
     index: u16,
     subindex: u8,
@@ -310,7 +400,7 @@ pub fn upload_4_bytes_frame(
 }
 
 pub fn emergency_frame(
-    id: u8,
+    id: NodeId,
     error_code: u16,
     error_register: u8,
     data: [u8; 5],
@@ -330,6 +420,11 @@ pub fn emergency_frame(
     )
 }
 
+/// Emergency frame with error code 0x0000, sent after all errors are cleared.
+pub fn emergency_clear_frame(id: NodeId, error_register: u8) -> CANOpenFrameResult {
+    emergency_frame(id, 0x0000, error_register, [0; 5])
+}
+
 pub fn get_mode(message: &CANOpenFrame) -> State {
     match message.data()[0] & 0x80 {
         0x04 => State::Stopped,
@@ -338,3 +433,97 @@ pub fn get_mode(message: &CANOpenFrame) -> State {
         _ => State::UnknownState,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_emergency_clear_frame() {
+        let frame = emergency_clear_frame(node(0x05), 0x00).unwrap();
+        assert_eq!(0x085, frame.cob_id());
+        assert_eq!(8, frame.length());
+        assert_eq!([0, 0, 0, 0, 0, 0, 0, 0], frame.data());
+        let frame = emergency_clear_frame(node(0x05), 0x01).unwrap();
+        assert_eq!([0, 0, 0x01, 0, 0, 0, 0, 0], frame.data());
+    }
+
+    #[test]
+    fn test_sdo_raw_frame() {
+        // expedited bit set but size not indicated
+        let frame = sdo_raw_frame(node(0x05), 0x600, 0x22, 0x2000, 0x01, [1, 2, 3, 4]).unwrap();
+        assert_eq!(0x605, frame.cob_id());
+        assert_eq!(8, frame.length());
+        assert_eq!([0x22, 0x00, 0x20, 0x01, 1, 2, 3, 4], frame.data());
+    }
+
+    #[test]
+    fn test_standalone_sdo_frames() {
+        let frame = upload_request_frame(node(0x7F), 0x600, 0x2001, 0x02).unwrap();
+        assert_eq!(0x67F, frame.cob_id());
+        assert_eq!([0x40, 0x01, 0x20, 0x02, 0, 0, 0, 0], frame.data());
+
+        let frame = download_1_byte_frame(node(0x7F), 0x600, 0x2001, 0x02, 0xAB).unwrap();
+        assert_eq!([0x2F, 0x01, 0x20, 0x02, 0xAB, 0, 0, 0], frame.data());
+        let frame = download_2_bytes_frame(node(0x7F), 0x600, 0x2001, 0x02, [0xAB, 0xCD]).unwrap();
+        assert_eq!([0x2B, 0x01, 0x20, 0x02, 0xAB, 0xCD, 0, 0], frame.data());
+        let frame =
+            download_3_bytes_frame(node(0x7F), 0x600, 0x2001, 0x02, [0xAB, 0xCD, 0xEF]).unwrap();
+        assert_eq!([0x27, 0x01, 0x20, 0x02, 0xAB, 0xCD, 0xEF, 0], frame.data());
+
+        let frame = sdo_abort_frame(node(0x7F), 0x600, 0x2001, 0x02, 0x0504_0000).unwrap();
+        assert_eq!(0x67F, frame.cob_id());
+        assert_eq!(
+            [0x80, 0x01, 0x20, 0x02, 0x00, 0x00, 0x04, 0x05],
+            frame.data()
+        );
+    }
+
+    #[test]
+    fn test_download_frame() {
+        for (data, expected) in [
+            (
+                &[0xAB][..],
+                download_1_byte_frame(node(0x05), 0x600, 0x2001, 0x02, 0xAB),
+            ),
+            (
+                &[0xAB, 0xCD][..],
+                download_2_bytes_frame(node(0x05), 0x600, 0x2001, 0x02, [0xAB, 0xCD]),
+            ),
+            (
+                &[1, 2, 3][..],
+                download_3_bytes_frame(node(0x05), 0x600, 0x2001, 0x02, [1, 2, 3]),
+            ),
+            (
+                &[1, 2, 3, 4][..],
+                download_4_bytes_frame(node(0x05), 0x600, 0x2001, 0x02, [1, 2, 3, 4]),
+            ),
+        ] {
+            assert_eq!(
+                expected.unwrap(),
+                download_frame(node(0x05), 0x600, 0x2001, 0x02, data).unwrap()
+            );
+        }
+        assert!(download_frame(node(0x05), 0x600, 0x2001, 0x02, &[]).is_err());
+        assert!(download_frame(node(0x05), 0x600, 0x2001, 0x02, &[0; 5]).is_err());
+    }
+
+    #[test]
+    fn test_node_id_range() {
+        assert!(NodeId::new(0x80).is_err());
+        assert!(NodeId::try_from(0xFF).is_err());
+        assert_eq!(0x7F, u8::from(NodeId::new(0x7F).unwrap()));
+        let frame = heartbeat_frame(node(0x7F), State::Operational).unwrap();
+        assert_eq!(0x77F, frame.cob_id());
+    }
+
+    #[test]
+    fn test_upload_initiate_segmented_frame() {
+        let frame = upload_initiate_segmented_frame(node(0x05), 0x580, 0x1008, 0x00, 300).unwrap();
+        assert_eq!(0x585, frame.cob_id());
+        assert_eq!(
+            [0x41, 0x08, 0x10, 0x00, 0x2C, 0x01, 0x00, 0x00],
+            frame.data()
+        );
+    }
+}
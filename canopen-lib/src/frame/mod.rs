@@ -1,3 +1,5 @@
+#![allow(non_local_definitions)]
+
 mod builders;
 pub use self::builders::*;
 pub use crate::split::Split;
@@ -8,9 +10,10 @@ use num_enum::TryFromPrimitive;
 
 use enum_display_derive::*;
 use std::fmt::Display;
+#[cfg(feature = "socketcan")]
 use tokio_socketcan::CANFrame;
 
-use crate::SDOServerResponse;
+use crate::sdo::{self, SDOServerResponse};
 
 #[derive(Debug, Fail)]
 pub enum CANOpenFrameError {
@@ -18,6 +21,57 @@ pub enum CANOpenFrameError {
     InvalidCOBID { cob_id: u32 },
     #[fail(display = "data length should not exceed 8 bytes ({} > 8)", length)]
     InvalidDataLength { length: usize },
+    #[fail(display = "node id {:#x} is out of range 0x00-0x7f", node_id)]
+    InvalidNodeId { node_id: u8 },
+    #[fail(display = "expedited SDO data must be 1 to 4 bytes ({})", length)]
+    InvalidSdoDataLength { length: usize },
+    #[fail(display = "extended (29 bit) CAN id {:#x} is not supported", id)]
+    ExtendedFrameUnsupported { id: u32 },
+}
+
+/// Node id checked to be in range 0x00..=0x7f, 0 addresses all nodes in NMT
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(u8);
+
+impl NodeId {
+    pub fn new(node_id: u8) -> Result<NodeId, CANOpenFrameError> {
+        if node_id <= 0x7f {
+            Ok(NodeId(node_id))
+        } else {
+            Err(CANOpenFrameError::InvalidNodeId { node_id })
+        }
+    }
+}
+
+impl TryFrom<u8> for NodeId {
+    type Error = CANOpenFrameError;
+    fn try_from(node_id: u8) -> Result<Self, Self::Error> {
+        NodeId::new(node_id)
+    }
+}
+
+impl From<NodeId> for u8 {
+    fn from(node_id: NodeId) -> u8 {
+        node_id.0
+    }
+}
+
+impl From<NodeId> for u32 {
+    fn from(node_id: NodeId) -> u32 {
+        node_id.0 as u32
+    }
+}
+
+impl std::fmt::Display for NodeId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:#04x}", self.0)
+    }
+}
+
+// node id literal in tests
+#[cfg(test)]
+pub(crate) fn node(node_id: u8) -> NodeId {
+    NodeId::new(node_id).unwrap()
 }
 
 #[allow(non_camel_case_types, dead_code)]
@@ -42,7 +96,14 @@ pub enum FrameType {
                               // Unused_1111, causes an error
 }
 
-#[derive(Debug, PartialEq)]
+impl FrameType {
+    /// Split a COB-ID into frame type and node id
+    pub fn from_cob_id(cob_id: u32) -> Result<(FrameType, u8), CANOpenFrameError> {
+        extract_frame_type_and_node_id(cob_id)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct CANOpenFrame {
     _node_id: u8,
     _frame_type: FrameType,
@@ -152,11 +213,82 @@ impl CANOpenFrame {
         const TYPE_START_BIT: u8 = 7;
         self._node_id as u32 + ((self._frame_type as u32) << TYPE_START_BIT)
     }
-}
 
-#[allow(clippy::from_over_into)]
-impl Into<CANFrame> for CANOpenFrame {
-    fn into(self) -> CANFrame {
+    /// Compare frame type, node id and payload but ignore the RTR flag
+    /// and the toggle bit of SDO command bytes
+    pub fn semantically_eq(&self, other: &Self) -> bool {
+        if self._frame_type != other._frame_type
+            || self._node_id != other._node_id
+            || self._length != other._length
+        {
+            return false;
+        }
+        let length = self._length as usize;
+        match self._frame_type {
+            FrameType::SsdoTx | FrameType::SsdoRx if length > 0 => {
+                (self._data[0] & !sdo::TOGGLE_FLAG) == (other._data[0] & !sdo::TOGGLE_FLAG)
+                    && self._data[1..length] == other._data[1..length]
+            }
+            _ => self._data[..length] == other._data[..length],
+        }
+    }
+
+    /// Index and subindex of SDO frames that carry them (initiate and abort),
+    /// `None` for segment frames and non SDO frames
+    pub fn sdo_index(&self) -> Option<(u16, u8)> {
+        if self._length < 4 {
+            return None;
+        }
+        let command_specifier = (self._data[0] & sdo::CCS_MASK) >> sdo::CCS_SHIFT;
+        let with_index = match self._frame_type {
+            // client request: initiate download, initiate upload, abort
+            FrameType::SsdoRx => matches!(command_specifier, 1 | 2 | 4),
+            // server response: initiate upload, initiate download, abort
+            FrameType::SsdoTx => matches!(command_specifier, 2..=4),
+            _ => false,
+        };
+        if with_index {
+            let index = (self._data[1] as u16) + ((self._data[2] as u16) << 8);
+            Some((index, self._data[3]))
+        } else {
+            None
+        }
+    }
+
+    /// One line with fixed width columns: type, COB-ID, node and decoded payload
+    pub fn format_aligned(&self) -> String {
+        self.format_aligned_width(usize::MAX)
+    }
+
+    /// Like `format_aligned`, cut to at most `width` characters
+    pub fn format_aligned_width(&self, width: usize) -> String {
+        let payload = match self._frame_type {
+            FrameType::SsdoTx | FrameType::SsdoRx => SDOServerResponse::parse(self)
+                .map(|sdo_response| sdo_response.to_string().trim_end().to_string())
+                .unwrap_or_default(),
+            _ => self._data[0..self._length as usize]
+                .iter()
+                .map(|byte| format!("{:02X}", byte))
+                .collect::<Vec<_>>()
+                .join(" "),
+        };
+        let line = format!(
+            "{:<15} {:#05x} {:#04x}  {}",
+            self._frame_type.to_string(),
+            self.cob_id(),
+            self._node_id,
+            payload
+        );
+        line.chars()
+            .take(width)
+            .collect::<String>()
+            .trim_end()
+            .to_string()
+    }
+
+    /// Wire representation of the frame, without giving up the CANOpen frame
+    #[cfg(feature = "socketcan")]
+    pub fn to_can_frame(&self) -> CANFrame {
         // every CANOpen frame is a CAN frame this conversion shall not cause an error
         CANFrame::new(
             self.cob_id(),
@@ -168,13 +300,34 @@ impl Into<CANFrame> for CANOpenFrame {
     }
 }
 
+#[cfg(feature = "socketcan")]
+#[allow(clippy::from_over_into)]
+impl Into<CANFrame> for CANOpenFrame {
+    fn into(self) -> CANFrame {
+        self.to_can_frame()
+    }
+}
+
+#[cfg(feature = "socketcan")]
 impl TryFrom<CANFrame> for CANOpenFrame {
     type Error = Error;
     fn try_from(frame: CANFrame) -> Result<Self, Self::Error> {
+        if frame.is_extended() {
+            return Err(CANOpenFrameError::ExtendedFrameUnsupported { id: frame.id() }.into());
+        }
         CANOpenFrame::new_with_rtr(frame.id(), frame.data(), frame.is_rtr())
     }
 }
 
+/// True for the error of decoding a 29 bit frame, such frames share the bus
+/// but are never CANopen frames
+pub fn is_extended_frame_error(error: &Error) -> bool {
+    matches!(
+        error.downcast_ref::<CANOpenFrameError>(),
+        Some(CANOpenFrameError::ExtendedFrameUnsupported { .. })
+    )
+}
+
 fn extract_frame_type_and_node_id(cob_id: u32) -> Result<(FrameType, u8), CANOpenFrameError> {
     if cob_id > 0x77F {
         // 0x77f is equivalent 11 bit
@@ -189,3 +342,97 @@ fn extract_frame_type_and_node_id(cob_id: u32) -> Result<(FrameType, u8), CANOpe
         .map_err(|_| CANOpenFrameError::InvalidCOBID { cob_id })?;
     Ok((frame_type, node_id))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "socketcan")]
+    #[test]
+    fn test_extended_frame_rejected() {
+        let frame = CANFrame::new(0x0001_0181, &[1], false, false).unwrap();
+        let error = CANOpenFrame::try_from(frame).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<CANOpenFrameError>(),
+            Some(CANOpenFrameError::ExtendedFrameUnsupported { id: 0x0001_0181 })
+        ));
+        assert!(is_extended_frame_error(&error));
+        // e.g. an LSS frame, standard but outside the CANopen COB-ID scheme
+        let frame = CANFrame::new(0x7E5, &[1], false, false).unwrap();
+        assert!(!is_extended_frame_error(
+            &CANOpenFrame::try_from(frame).unwrap_err()
+        ));
+    }
+
+    #[test]
+    fn test_format_aligned() {
+        let pdo = CANOpenFrame::new(0x181, &[0x01, 0xAB]).unwrap();
+        assert_eq!("Tpdo1           0x181 0x01  01 AB", pdo.format_aligned());
+        let sync = CANOpenFrame::new(0x080, &[]).unwrap();
+        assert_eq!("SyncEmergency   0x080 0x00", sync.format_aligned());
+        assert_eq!("Tpdo1           0x181", pdo.format_aligned_width(21));
+    }
+
+    #[cfg(feature = "socketcan")]
+    #[test]
+    fn test_to_can_frame_keeps_frame() {
+        let frame = CANOpenFrame::new_with_rtr(0x23A, &[0x01, 0x02, 0x03], false).unwrap();
+        let can_frame = frame.to_can_frame();
+        assert_eq!(0x23A, can_frame.id());
+        assert_eq!(&[0x01, 0x02, 0x03], can_frame.data());
+        assert!(!can_frame.is_rtr());
+        assert_eq!(0x23A, frame.cob_id());
+    }
+
+    #[test]
+    fn test_semantically_eq_ignores_toggle_and_rtr() {
+        let segment = CANOpenFrame::new(0x5A1, &[0x00, 1, 2, 3, 4, 5, 6, 7]).unwrap();
+        let toggled = CANOpenFrame::new(0x5A1, &[0x10, 1, 2, 3, 4, 5, 6, 7]).unwrap();
+        assert!(segment.semantically_eq(&toggled));
+        assert_ne!(segment, toggled);
+
+        let pdo = CANOpenFrame::new(0x1E5, &[0x10, 0x02]).unwrap();
+        let pdo_rtr = CANOpenFrame::new_rtr(0x1E5, &[0x10, 0x02]).unwrap();
+        assert!(pdo.semantically_eq(&pdo_rtr));
+
+        // the toggle bit is only masked for SDO frames
+        let other_pdo = CANOpenFrame::new(0x1E5, &[0x00, 0x02]).unwrap();
+        assert!(!pdo.semantically_eq(&other_pdo));
+
+        let other_node = CANOpenFrame::new(0x5A2, &[0x00, 1, 2, 3, 4, 5, 6, 7]).unwrap();
+        assert!(!segment.semantically_eq(&other_node));
+    }
+
+    #[test]
+    fn test_frame_type_from_cob_id() {
+        assert_eq!(
+            (FrameType::NmtErrorControl, 0x1A),
+            FrameType::from_cob_id(0x71A).unwrap()
+        );
+        assert_eq!(
+            (FrameType::Tpdo1, 0x65),
+            FrameType::from_cob_id(0x1E5).unwrap()
+        );
+        assert_eq!(
+            (FrameType::Nmt, 0x00),
+            FrameType::from_cob_id(0x000).unwrap()
+        );
+        assert!(FrameType::from_cob_id(0x6A5).is_err()); // 0b1101 is unused
+        assert!(FrameType::from_cob_id(0x780).is_err());
+    }
+
+    #[test]
+    fn test_sdo_index() {
+        let upload_request = CANOpenFrame::new(0x612, &[0x40, 0x18, 0x10, 0x02, 0, 0, 0, 0]);
+        assert_eq!(Some((0x1018, 0x02)), upload_request.unwrap().sdo_index());
+
+        let upload_response = CANOpenFrame::new(0x592, &[0x43, 0x00, 0x10, 0x00, 1, 2, 3, 4]);
+        assert_eq!(Some((0x1000, 0x00)), upload_response.unwrap().sdo_index());
+
+        let segment = CANOpenFrame::new(0x592, &[0x00, 1, 2, 3, 4, 5, 6, 7]).unwrap();
+        assert_eq!(None, segment.sdo_index());
+
+        let pdo = CANOpenFrame::new(0x192, &[0x40, 0x18, 0x10, 0x02]).unwrap();
+        assert_eq!(None, pdo.sdo_index());
+    }
+}
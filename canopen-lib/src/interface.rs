@@ -0,0 +1,34 @@
+use async_trait::async_trait;
+use failure::Error;
+
+use crate::frame::CANOpenFrame;
+
+/// CAN backend the application layer sends and receives CANOpen frames with
+#[async_trait]
+pub trait CanInterface {
+    async fn send(&mut self, frame: CANOpenFrame) -> Result<(), Error>;
+
+    /// Next frame, `None` once the backend is closed
+    async fn recv(&mut self) -> Option<Result<CANOpenFrame, Error>>;
+}
+
+#[cfg(feature = "socketcan")]
+mod socketcan {
+    use super::*;
+    use futures_util::StreamExt;
+    use std::convert::TryFrom;
+    use tokio_socketcan::CANSocket;
+
+    #[async_trait]
+    impl CanInterface for CANSocket {
+        async fn send(&mut self, frame: CANOpenFrame) -> Result<(), Error> {
+            self.write_frame(frame.into())?.await?;
+            Ok(())
+        }
+
+        async fn recv(&mut self) -> Option<Result<CANOpenFrame, Error>> {
+            let frame = self.next().await?;
+            Some(frame.map_err(Error::from).and_then(CANOpenFrame::try_from))
+        }
+    }
+}
@@ -1,11 +1,18 @@
 pub mod canopen;
+pub mod diagnostics;
+#[cfg(feature = "socketcan")]
+pub mod error_frame;
 #[allow(unused_must_use)]
 #[allow(unused_variables)]
 pub mod frame;
+pub mod interface;
 pub mod split;
+#[cfg(feature = "socketcan")]
+pub mod util;
 
 pub use canopen::*;
 pub use frame::*;
+pub use interface::CanInterface;
 pub use parse_int::parse;
 
 use std::ops::RangeInclusive;
@@ -28,30 +35,50 @@ const PDO_COBID_RANGE: RangeInclusive<u32> = 0x180..=0x5ff;
 
 pub fn pdo_cobid_parser(s: &str) -> Result<u32, String> {
     let cobid = parse::<u32>(s).map_err(|x| format!("{} is not an integer", x))?;
-    if PDO_COBID_RANGE.contains(&cobid) {
-        Ok(cobid)
-    } else {
-        Err(format!(
+    pdo_cobid_check(cobid, false)
+}
+
+/// Like `pdo_cobid_parser` but only accepts COB-IDs a device receives (RPDO)
+pub fn rpdo_cobid_parser(s: &str) -> Result<u32, String> {
+    let cobid = parse::<u32>(s).map_err(|x| format!("{} is not an integer", x))?;
+    pdo_cobid_check(cobid, true)
+}
+
+/// Check a COB-ID is in the PDO range, and a receive PDO if `rpdo_only`,
+/// the error names the PDO and node the COB-ID belongs to
+pub fn pdo_cobid_check(cobid: u32, rpdo_only: bool) -> Result<u32, String> {
+    if !PDO_COBID_RANGE.contains(&cobid) {
+        return Err(format!(
             "Cob Id is not in range {:x}-{:x}",
             PDO_COBID_RANGE.start(),
             PDO_COBID_RANGE.end()
+        ));
+    }
+    let (frame_type, node_id) = FrameType::from_cob_id(cobid).map_err(|x| x.to_string())?;
+    let is_rpdo = matches!(
+        frame_type,
+        FrameType::Rpdo1 | FrameType::Rpdo2 | FrameType::Rpdo3 | FrameType::Rpdo4
+    );
+    if rpdo_only && !is_rpdo {
+        Err(format!(
+            "Cob Id {:#x} is {} of node {:#04x}, not an RPDO",
+            cobid, frame_type, node_id
         ))
+    } else {
+        Ok(cobid)
     }
 }
 
-const NODE_ID_RANGE: RangeInclusive<u32> = 0x00..=0x7f;
-
-pub fn nodeid_parser(s: &str) -> Result<u8, String> {
+pub fn nodeid_parser(s: &str) -> Result<NodeId, String> {
     let nodeid = parse::<u32>(s).map_err(|x| format!("{} is not an integer", x))?;
-    if NODE_ID_RANGE.contains(&nodeid) {
-        Ok(nodeid.try_into().unwrap())
-    } else {
-        Err(format!(
-            "Node Id is not in range {:x}-{:x}",
-            NODE_ID_RANGE.start(),
-            NODE_ID_RANGE.end()
-        ))
-    }
+    u8::try_from(nodeid)
+        .map_err(|_| format!("node id {:#x} is out of range 0x00-0x7f", nodeid))
+        .and_then(|nodeid| NodeId::new(nodeid).map_err(|x| x.to_string()))
+}
+
+/// Object address as "0x2000,0x01", or "0x2000" for subindex 0
+pub fn object_address_parser(s: &str) -> Result<ObjectAddress, String> {
+    s.parse::<ObjectAddress>().map_err(|x| x.to_string())
 }
 
 #[cfg(test)]
@@ -71,10 +98,21 @@ mod tests {
             (expected_data, 3),
             parse_payload_as_byte_sequence_semicolon_delimited("01;0b10;0x0_3")
         );
-        let expected_data: [u8; 8] = [06, 0x38, 0, 0, 0, 0, 0, 0];
+        let expected_data: [u8; 8] = [0x06, 0x38, 0, 0, 0, 0, 0, 0];
         assert_eq!(
             (expected_data, 4),
             parse_payload_as_byte_sequence_semicolon_delimited("0x06;0x38;0;0")
         );
     }
+
+    #[test]
+    fn test_pdo_cobid_parser() {
+        assert_eq!(Ok(0x181), pdo_cobid_parser("0x181"));
+        assert_eq!(Ok(0x201), rpdo_cobid_parser("0x201"));
+        assert_eq!(
+            Err("Cob Id 0x181 is Tpdo1 of node 0x01, not an RPDO".to_string()),
+            rpdo_cobid_parser("0x181")
+        );
+        assert!(pdo_cobid_parser("0x601").is_err());
+    }
 }
@@ -0,0 +1,70 @@
+use futures_timer::Delay;
+use log::{info, warn};
+use std::time::Duration;
+use tokio_socketcan::CANSocket;
+
+const INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+const MAX_BACKOFF: Duration = Duration::from_secs(5);
+
+/// Delays between reopen attempts, doubling from 100 ms up to 5 s.
+///
+/// Keep one per interface and `reset` it once frames arrive again, so an
+/// interface that keeps failing right after reopening backs off further.
+#[derive(Debug)]
+pub struct Backoff {
+    delay: Duration,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Backoff {
+            delay: INITIAL_BACKOFF,
+        }
+    }
+}
+
+impl Backoff {
+    /// Delay before the next attempt
+    pub fn next_delay(&mut self) -> Duration {
+        let delay = self.delay;
+        self.delay = std::cmp::min(self.delay * 2, MAX_BACKOFF);
+        delay
+    }
+
+    pub fn reset(&mut self) {
+        self.delay = INITIAL_BACKOFF;
+    }
+}
+
+/// Reopen a CAN interface after its socket failed or ended (e.g. bus-off).
+///
+/// Waits before every attempt as given by `backoff`, and only
+/// returns once the interface could be opened again.
+pub async fn reopen_on_error(interface: &str, backoff: &mut Backoff) -> CANSocket {
+    loop {
+        Delay::new(backoff.next_delay()).await;
+        match CANSocket::open(interface) {
+            Ok(socket) => {
+                info!("Reopened CAN interface {}", interface);
+                return socket;
+            }
+            Err(error) => warn!("Reopening {} failed: {}", interface, error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let mut backoff = Backoff::default();
+        let delays: Vec<u64> = (0..8)
+            .map(|_| backoff.next_delay().as_millis() as u64)
+            .collect();
+        assert_eq!(vec![100, 200, 400, 800, 1600, 3200, 5000, 5000], delays);
+        backoff.reset();
+        assert_eq!(Duration::from_millis(100), backoff.next_delay());
+    }
+}
@@ -11,13 +11,18 @@ use std::time::Duration;
 // use tokio;
 use tokio_socketcan::{CANFrame, CANSocket};
 
-use col::{self, nodeid_parser, pdo_cobid_parser, sdo::SDOServerResponse};
+use col::{
+    self, nodeid_parser, object_address_parser, pdo_cobid_parser,
+    sdo::{SDOAbortCode, SDOResult, SDOServerResponse},
+    Data, DataConversionError, DataType, NodeId, ObjectAddress,
+};
 use parse_int::parse;
 
 use futures::{
-    future::FutureExt, // for `.fuse()`
+    future::{Future, FutureExt}, // FutureExt for `.fuse()`
     pin_mut,
     select,
+    stream::{self, select_all, Stream},
 };
 
 use std::time::Instant;
@@ -25,9 +30,9 @@ use std::time::Instant;
 #[derive(Parser)]
 #[clap(author, version, about, long_about = None)]
 struct Cli {
-    /// CAN interface to read from, write to
-    #[clap(short, long, default_value_t = String::from("can0"))]
-    interface: String,
+    /// CAN interface to read from, write to - monitoring accepts several
+    #[clap(short, long, default_value = "can0", multiple_occurrences(true))]
+    interface: Vec<String>,
 
     /// Allow verbose output
     #[clap(flatten)]
@@ -40,10 +45,19 @@ struct Cli {
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ArgEnum, Debug)]
 enum ValueType {
     None,
+    Bool,
     U8,
     U16,
+    U24,
     U32,
+    U40,
+    U48,
+    U56,
     U64,
+    I24,
+    I40,
+    I48,
+    I56,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ArgEnum, Debug)]
@@ -55,49 +69,71 @@ enum FrameType {
     Err,
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ArgEnum, Debug)]
+enum TimestampMode {
+    Relative,
+    Absolute,
+    Delta,
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Read object directory
+    ///
+    /// The object is addressed as index,subindex, e.g. `rod 5 0x1018,0x01`.
+    /// The former form with the subindex as separate argument,
+    /// `rod 5 0x1018 0x01`, is still accepted.
     Rod {
         /// NodeId - range 0 .. 127 aka 0x00 .. 0x7f
+        #[clap(value_parser = nodeid_parser)]
+        node: NodeId,
+
+        /// Object address - index,subindex as 0x1018,0x01 or index only for subindex 0
+        #[clap(value_parser = object_address_parser)]
+        address: ObjectAddress,
+
+        /// Object subindex - deprecated, give it as part of the address
         #[clap(value_parser = parse::<u8>)]
-        node: u8,
+        subindex: Option<u8>,
 
-        /// Object index - range 0x0000 .. 0xffff
-        #[clap(value_parser = parse::<u16>)]
-        index: u16,
+        /// Decode the value as ValueType instead of showing the raw data,
+        /// at most 4 bytes wide
+        #[clap(short = 't', long, value_parser = expedited_value_type,
+               possible_values = EXPEDITED_VALUE_TYPES)]
+        value_type: Option<ValueType>,
 
-        /// Object subindex - index 0x00 .. 0xff
-        #[clap(default_value_t = 0x00, value_parser = parse::<u8>)]
-        subindex: u8,
+        /// Accept responses matching the index only, for devices that
+        /// always answer with subindex 0
+        #[clap(long)]
+        lenient_subindex: bool,
     },
 
     /// Write object directory
+    ///
+    /// The object is addressed as index,subindex, e.g. `wod 5 0x2000,0x01 u8 7`,
+    /// this replaces the separate index and subindex arguments of
+    /// `wod 5 0x2000 0x01 u8 7`.
     Wod {
         /// NodeId - range 0..127 aka 0x00 .. 0x7f
-        #[clap(value_parser = parse::<u8>)]
-        node: u8,
-
-        /// Object index - range 0x0000 .. 0xffff
-        #[clap(value_parser = parse::<u16>)]
-        index: u16,
+        #[clap(value_parser = nodeid_parser)]
+        node: NodeId,
 
-        /// Object subindex - index 0x00 .. 0xff
-        #[clap(value_parser = parse::<u8>)]
-        subindex: u8,
+        /// Object address - index,subindex as 0x2000,0x01 or index only for subindex 0
+        #[clap(value_parser = object_address_parser)]
+        address: ObjectAddress,
 
-        /// ValueType of the value
-        #[clap(arg_enum)]
+        /// ValueType of the value, at most 4 bytes wide
+        #[clap(value_parser = expedited_value_type, possible_values = EXPEDITED_VALUE_TYPES)]
         value_type: ValueType,
 
-        /// Object value - u32 as 0xabc_def01 or b0011_1001_0 or 123
-        #[clap(value_parser = parse::<u32>)]
-        value: u32,
+        /// Object value - u32 as 0xabc_def01 or b0011_1001_0 or 123,
+        /// bool as true, false, 1 or 0
+        value: String,
     },
 
     /// write PDO
     Pdo {
-        /// CobId - range 0x180...0x5ff aka 512 max
+        /// CobId - range 0x180...0x5ff
         #[clap(value_parser = pdo_cobid_parser)]
         cobid: u32,
 
@@ -105,20 +141,31 @@ enum Commands {
         #[clap(short, long)]
         remote: bool,
 
+        /// Only accept COB-IDs a device receives (RPDO)
+        #[clap(long, conflicts_with = "remote")]
+        rpdo_only: bool,
+
         /// ValueType of the value
         #[clap(arg_enum)]
         value_type: ValueType,
 
-        /// PDO payload in hexadecimal with leading 0x maximum 8 bytes
-        #[clap(value_parser = parse::<u64>)]
-        value: u64,
+        /// PDO payload in hexadecimal with leading 0x maximum 8 bytes,
+        /// bool as true, false, 1 or 0
+        value: String,
+    },
+
+    /// Explain an SDO abort code
+    Abort {
+        /// Abort code - u32 as 0x0609_0011 or 101253137
+        #[clap(value_parser = parse::<u32>)]
+        code: u32,
     },
 
     /// Monitor traffic
     Mon {
         /// NodeId - range 0..127
         #[clap(short, long, value_parser = nodeid_parser, multiple_occurrences(true))]
-        nodes: Vec<u8>,
+        nodes: Vec<NodeId>,
 
         /// CobId - range 0..0x3ff
         #[clap(short, long, value_parser = pdo_cobid_parser, multiple_occurrences(true))]
@@ -131,58 +178,137 @@ enum Commands {
         /// Show relative time stamps
         #[clap(short, long)]
         timestamp: bool,
+
+        /// Time base of the time stamps (implies --timestamp)
+        #[clap(arg_enum, long)]
+        timestamp_mode: Option<TimestampMode>,
+
+        /// Cut output lines to this many characters for narrow terminals
+        #[clap(short, long)]
+        width: Option<usize>,
     },
 }
 
-async fn client_server_communication_timeout() {
-    debug!("Set response timeout to 3 seconds");
-    let _timeout = Delay::new(Duration::from_secs(3)).await;
+// little endian wire encoding of the value truncated to the value type
+fn encode_value(value_type: ValueType, value: u64) -> Vec<u8> {
+    let data = match value_type {
+        ValueType::None => return vec![],
+        ValueType::Bool => Data::BOOLEAN(value != 0),
+        ValueType::U8 => Data::UNSIGNED8(value as u8),
+        ValueType::U16 => Data::UNSIGNED16(value as u16),
+        ValueType::U24 => Data::UNSIGNED24(value as i32),
+        ValueType::U32 => Data::UNSIGNED32(value as u32),
+        ValueType::U40 => Data::UNSIGNED40(value),
+        ValueType::U48 => Data::UNSIGNED48(value),
+        ValueType::U56 => Data::UNSIGNED56(value),
+        ValueType::U64 => Data::UNSIGNED64(value),
+        ValueType::I24 => Data::INTEGER24(value as i32),
+        ValueType::I40 => Data::INTEGER40(value as i64),
+        ValueType::I48 => Data::INTEGER48(value as i64),
+        ValueType::I56 => Data::INTEGER56(value as i64),
+    };
+    Vec::<u8>::try_from(data).unwrap()
+}
+
+// integer value of a wod or pdo argument, bool values are true/false or 1/0
+fn parse_value(value_type: ValueType, value: &str) -> Result<u64, String> {
+    match value_type {
+        ValueType::Bool => Data::from_str(value, DataType::BOOLEAN)
+            .and_then(bool::try_from)
+            .map(u64::from)
+            .map_err(|_| format!("{} is not a bool, use true, false, 1 or 0", value)),
+        _ => parse::<u64>(value).map_err(|x| format!("{} is not an integer: {}", value, x)),
+    }
+}
+
+fn data_type(value_type: ValueType) -> DataType {
+    match value_type {
+        ValueType::None => DataType::NIL,
+        ValueType::Bool => DataType::BOOLEAN,
+        ValueType::U8 => DataType::UNSIGNED8,
+        ValueType::U16 => DataType::UNSIGNED16,
+        ValueType::U24 => DataType::UNSIGNED24,
+        ValueType::U32 => DataType::UNSIGNED32,
+        ValueType::U40 => DataType::UNSIGNED40,
+        ValueType::U48 => DataType::UNSIGNED48,
+        ValueType::U56 => DataType::UNSIGNED56,
+        ValueType::U64 => DataType::UNSIGNED64,
+        ValueType::I24 => DataType::INTEGER24,
+        ValueType::I40 => DataType::INTEGER40,
+        ValueType::I48 => DataType::INTEGER48,
+        ValueType::I56 => DataType::INTEGER56,
+    }
+}
+
+const EXPEDITED_VALUE_TYPES: [&str; 7] = ["none", "bool", "u8", "u16", "u24", "u32", "i24"];
+
+// value types that fit into an expedited SDO transfer (up to 4 bytes)
+fn expedited_value_type(s: &str) -> Result<ValueType, String> {
+    let value_type = ValueType::from_str(s, false)?;
+    if data_type(value_type).bit_size().unwrap_or(0) > 32 {
+        return Err(format!("{} does not fit into an expedited SDO", s));
+    }
+    Ok(value_type)
+}
+
+// value of an expedited upload decoded as value type,
+// data_size is the number of data bytes indicated by the response
+fn decode_value(value_type: ValueType, data: u32, data_size: Option<u8>) -> Result<Data, String> {
+    let data_type = data_type(value_type);
+    let size = data_type.bit_size().unwrap_or(0).div_ceil(8);
+    let bytes = data.to_le_bytes();
+    if size > bytes.len() {
+        return Err(DataConversionError::InvalidDataLength(bytes.len(), data_type).to_string());
+    }
+    if let Some(data_size) = data_size {
+        if data_size as usize != size {
+            return Err(
+                DataConversionError::InvalidDataLength(data_size as usize, data_type).to_string(),
+            );
+        }
+    }
+    Data::from_le_bytes(&bytes[..size], data_type).map_err(|x| x.to_string())
+}
+
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(3);
+
+// true if the worker completed before the timeout
+async fn completed_within(worker: impl Future<Output = ()>, timeout: Duration) -> bool {
+    debug!("Set response timeout to {:?}", timeout);
+    let worker = worker.fuse();
+    let timeout = Delay::new(timeout).fuse();
+
+    pin_mut!(worker, timeout);
+
+    select! {
+        () = worker => true,
+        () = timeout => false,
+    }
 }
 
 async fn write_remote_object(
     can_socket: &mut CANSocket,
-    node: u8,
-    index: u16,
-    subindex: u8,
+    node: NodeId,
+    address: ObjectAddress,
     value_type: ValueType,
     value: u32,
 ) {
     const SDO_RECEIVE: u32 = 0x600;
-    let frame: CANFrame = match value_type {
-        ValueType::U8 => {
-            col::download_1_byte_frame(node, SDO_RECEIVE, index, subindex, value as u8)
-                .unwrap()
-                .into()
-        }
-        ValueType::U16 => {
-            let buffer: [u8; 2] = [
-                // little endian encoded
-                (value & 0xff_u32) as u8,
-                ((value >> 8) & 0xff_u32) as u8,
-            ];
-            col::download_2_bytes_frame(node, SDO_RECEIVE, index, subindex, buffer)
-                .unwrap()
-                .into()
-        }
-        ValueType::U32 => {
-            let buffer: [u8; 4] = [
-                // little endian encoded
-                (value & 0xff_u32) as u8,
-                ((value >> 8) & 0xff_u32) as u8,
-                ((value >> 16) & 0xff_u32) as u8,
-                ((value >> 24) & 0xff_u32) as u8,
-            ];
-            col::download_4_bytes_frame(node, SDO_RECEIVE, index, subindex, buffer)
-                .unwrap()
-                .into()
-        }
-        _ => {
-            error!("{:?} is not supported for this SDO", value_type);
-            col::upload_request_frame(node, SDO_RECEIVE, index, subindex)
-                .unwrap()
-                .into()
-        }
-    };
+    let data = encode_value(value_type, value as u64);
+    let size = data.len();
+    debug!(
+        "SDO download start: node={} object={} size={}",
+        node, address, size
+    );
+    let start_time = Instant::now();
+    let frame: CANFrame =
+        match col::download_frame(node, SDO_RECEIVE, address.index, address.subindex, &data) {
+            Ok(frame) => frame.into(),
+            Err(error) => {
+                error!("{:?} is not supported for this SDO: {}", value_type, error);
+                quit::with_code(1);
+            }
+        };
 
     match match can_socket.write_frame(frame) {
         Ok(x) => x,
@@ -201,48 +327,99 @@ async fn write_remote_object(
     }
 
     // read the response
-    while let Some(Ok(frame)) = can_socket.next().await {
+    while let Some(frame) = can_socket.next().await {
+        let frame = match frame {
+            Ok(frame) => frame,
+            Err(error) => {
+                error!("Error reading: {}", error);
+                quit::with_code(1);
+            }
+        };
         match col::CANOpenFrame::try_from(frame) {
             Ok(frame) => {
-                if frame.node_id() == node && frame.frame_type() == col::frame::FrameType::SsdoTx {
+                if is_download_response(&frame, node, address) {
+                    debug!(
+                        "SDO download end: node={} object={} size={} elapsed={:?}",
+                        node,
+                        address,
+                        size,
+                        start_time.elapsed()
+                    );
+                    let acknowledged = SDOServerResponse::parse(&frame)
+                        .and_then(|sdo_response| sdo_response.download_acknowledged());
+                    if let Err(error) = acknowledged {
+                        error!("SDO download of {} @ {} failed: {}", address, node, error);
+                        quit::with_code(1);
+                    }
                     break;
                 }
             }
-            Err(e) => {
-                error!("{}", e);
-                break;
-            }
+            Err(e) if col::is_extended_frame_error(&e) => continue,
+            Err(e) => debug!("Skip frame: {}", e),
         }
     }
 }
 
+// SDO server response of the node for the object, a late response to an
+// earlier transfer is not the acknowledgement of this one
+fn is_download_response(frame: &col::CANOpenFrame, node: NodeId, address: ObjectAddress) -> bool {
+    frame.node_id() == u8::from(node)
+        && frame.frame_type() == col::frame::FrameType::SsdoTx
+        && frame.sdo_index() == Some((address.index, address.subindex))
+}
+
 async fn write_remote_object_with_acknowledge_check(
     can_socket: &mut CANSocket,
-    node: u8,
-    index: u16,
-    subindex: u8,
+    node: NodeId,
+    address: ObjectAddress,
     value_type: ValueType,
     value: u32,
 ) {
-    let worker = write_remote_object(can_socket, node, index, subindex, value_type, value).fuse();
-    let timeout = client_server_communication_timeout().fuse();
-
-    pin_mut!(worker, timeout);
+    let worker = write_remote_object(can_socket, node, address, value_type, value);
+    if completed_within(worker, RESPONSE_TIMEOUT).await {
+        info!("Remote object has been updated");
+    } else {
+        error!("Error: Object directory writing not acknowledged within 3 sec timeout");
+        quit::with_code(1);
+    }
+}
 
-    select! {
-        () = worker => info!("Remote object has been updated"),
-        () = timeout => {
-            error!("Error: Object directory writing not acknowledged within 3 sec timeout");
-            quit::with_code(1);
-        }
+// address of the former `rod <node> <index> <subindex>` form
+fn with_subindex(address: ObjectAddress, subindex: Option<u8>) -> Result<ObjectAddress, String> {
+    match subindex {
+        None => Ok(address),
+        Some(subindex) if address.subindex == 0 => Ok(ObjectAddress::new(address.index, subindex)),
+        Some(subindex) => Err(format!(
+            "subindex {:#04x} given twice, address {} already has one",
+            subindex, address
+        )),
     }
 }
 
-async fn read_remote_object(can_socket: &mut CANSocket, node: u8, index: u16, subindex: u8) {
+// response belongs to the requested object
+fn response_matches(
+    sdo_response: &SDOServerResponse,
+    address: ObjectAddress,
+    lenient_subindex: bool,
+) -> bool {
+    sdo_response.index == address.index
+        && (lenient_subindex || sdo_response.subindex == address.subindex)
+}
+
+async fn read_remote_object(
+    can_socket: &mut CANSocket,
+    node: NodeId,
+    address: ObjectAddress,
+    value_type: Option<ValueType>,
+    lenient_subindex: bool,
+) {
     const SDO_RECEIVE: u32 = 0x600;
-    let frame: CANFrame = col::upload_request_frame(node, SDO_RECEIVE, index, subindex)
-        .unwrap()
-        .into();
+    debug!("SDO upload start: node={} object={}", node, address);
+    let start_time = Instant::now();
+    let frame: CANFrame =
+        col::upload_request_frame(node, SDO_RECEIVE, address.index, address.subindex)
+            .unwrap()
+            .into();
     match match can_socket.write_frame(frame) {
         Ok(x) => x,
         Err(error) => {
@@ -260,53 +437,91 @@ async fn read_remote_object(can_socket: &mut CANSocket, node: u8, index: u16, su
     }
 
     // read the response
-    while let Some(Ok(frame)) = can_socket.next().await {
+    while let Some(frame) = can_socket.next().await {
+        let frame = match frame {
+            Ok(frame) => frame,
+            Err(error) => {
+                error!("Error reading: {}", error);
+                quit::with_code(1);
+            }
+        };
         match col::CANOpenFrame::try_from(frame) {
             Ok(frame) => {
-                if frame.node_id() == node && frame.frame_type() == col::frame::FrameType::SsdoTx {
-                    let sdo_response = SDOServerResponse::parse(&frame)
-                        .map_err(|x| error!("{}", x))
-                        .unwrap();
-                    if sdo_response.index == index && sdo_response.subindex == subindex {
-                        println!(
-                            "CANOpen Object {:#06x},{:#04x} @ {:#04x}: {:#x}",
-                            index, subindex, node, sdo_response.data
+                if frame.node_id() == u8::from(node)
+                    && frame.frame_type() == col::frame::FrameType::SsdoTx
+                {
+                    let sdo_response = match SDOServerResponse::parse(&frame) {
+                        Ok(sdo_response) => sdo_response,
+                        Err(error) => {
+                            error!("{}", error);
+                            quit::with_code(1);
+                        }
+                    };
+                    let response_address =
+                        ObjectAddress::new(sdo_response.index, sdo_response.subindex);
+                    debug!(
+                        "SDO upload end: node={} object={} result={} elapsed={:?}",
+                        node,
+                        response_address,
+                        sdo_response.result,
+                        start_time.elapsed()
+                    );
+                    if let SDOResult::Failure = sdo_response.result {
+                        error!(
+                            "SDO upload of {} @ {} aborted: {}",
+                            address,
+                            node,
+                            abort_code_description(sdo_response.data)
                         );
+                        quit::with_code(1);
+                    }
+                    if response_matches(&sdo_response, address, lenient_subindex) {
+                        let data_size =
+                            col::decode_sdo_frame_command(&frame).and_then(|info| info.data_size);
+                        match value_type.map(|value_type| {
+                            decode_value(value_type, sdo_response.data, data_size)
+                        }) {
+                            None => println!(
+                                "CANOpen Object {} @ {}: {:#x}",
+                                address, node, sdo_response.data
+                            ),
+                            Some(Ok(value)) => {
+                                println!("CANOpen Object {} @ {}: {}", address, node, value)
+                            }
+                            Some(Err(error)) => {
+                                error!("{}", error);
+                                quit::with_code(1);
+                            }
+                        }
                         break;
                     } else {
-                        println!(
-                            "CANOpen -- Object {:#06x},{:#04x} @ {:#04x}: {:#x}",
-                            sdo_response.index, sdo_response.subindex, node, sdo_response.data
+                        error!(
+                            "Unexpected SDO response for object {} @ {}, expected {}",
+                            response_address, node, address
                         );
-                        break;
+                        quit::with_code(1);
                     }
                 }
             }
-            Err(e) => {
-                error!("{}", e);
-                break;
-            }
+            Err(e) if col::is_extended_frame_error(&e) => continue,
+            Err(e) => debug!("Skip frame: {}", e),
         }
     }
 }
 
 async fn read_remote_object_with_acknowledge_check(
     can_socket: &mut CANSocket,
-    node: u8,
-    index: u16,
-    subindex: u8,
+    node: NodeId,
+    address: ObjectAddress,
+    value_type: Option<ValueType>,
+    lenient_subindex: bool,
 ) {
-    let worker = read_remote_object(can_socket, node, index, subindex).fuse();
-    let timeout = client_server_communication_timeout().fuse();
-
-    pin_mut!(worker, timeout);
-
-    select! {
-        () = worker => info!("Remote object has been updated"),
-        () = timeout => {
-            error!("Error: Object directory reading not responded within 3 sec timeout");
-            quit::with_code(1);
-        }
+    let worker = read_remote_object(can_socket, node, address, value_type, lenient_subindex);
+    if completed_within(worker, RESPONSE_TIMEOUT).await {
+        info!("Remote object has been read");
+    } else {
+        error!("Error: Object directory reading not responded within 3 sec timeout");
+        quit::with_code(1);
     }
 }
 
@@ -317,45 +532,8 @@ async fn send_pdo(
     value_type: ValueType,
     value: u64,
 ) {
-    let buffer: [u8; 8];
-    let data: &[u8] = match value_type {
-        ValueType::None => &[],
-        ValueType::U8 => {
-            buffer = [value as u8, 0, 0, 0, 0, 0, 0, 0];
-            &buffer[0..=0]
-        }
-        ValueType::U16 => {
-            buffer = [value as u8, (value >> 8) as u8, 0, 0, 0, 0, 0, 0];
-            &buffer[0..=1]
-        }
-        ValueType::U32 => {
-            buffer = [
-                value as u8,
-                (value >> 8) as u8,
-                (value >> 16) as u8,
-                (value >> 24) as u8,
-                0,
-                0,
-                0,
-                0,
-            ];
-            &buffer[0..=3]
-        }
-        ValueType::U64 => {
-            buffer = [
-                value as u8,
-                (value >> 8) as u8,
-                (value >> 16) as u8,
-                (value >> 24) as u8,
-                (value >> 32) as u8,
-                (value >> 40) as u8,
-                (value >> 48) as u8,
-                (value >> 56) as u8,
-            ];
-            &buffer[0..=7]
-        }
-    };
-    let frame: CANFrame = col::CANOpenFrame::new_with_rtr(cob_id as u32, data, is_rtr)
+    let data = encode_value(value_type, value);
+    let frame: CANFrame = col::CANOpenFrame::new_with_rtr(cob_id, &data, is_rtr)
         .unwrap()
         .into();
     match can_socket.write_frame(frame) {
@@ -369,6 +547,117 @@ async fn send_pdo(
     .unwrap();
 }
 
+fn abort_code_description(code: u32) -> String {
+    format!("{:#010x}: {}", code, SDOAbortCode::from(code))
+}
+
+// frames of an interface including error frames,
+// the socket is reopened whenever it fails or ends
+fn resilient_frames(
+    interface: String,
+    socket: CANSocket,
+) -> impl Stream<Item = (String, CANFrame)> {
+    receive_error_frames(&interface, &socket);
+    let backoff = col::util::Backoff::default();
+    stream::unfold(
+        (interface, socket, backoff),
+        |(interface, mut socket, mut backoff)| async move {
+            loop {
+                match socket.next().await {
+                    Some(Ok(frame)) => {
+                        // the interface works again, start over with short delays
+                        backoff.reset();
+                        return Some(((interface.clone(), frame), (interface, socket, backoff)));
+                    }
+                    Some(Err(error)) => error!("Error reading {}: {}", interface, error),
+                    None => error!("Interface {} closed", interface),
+                }
+                socket = col::util::reopen_on_error(&interface, &mut backoff).await;
+                receive_error_frames(&interface, &socket);
+            }
+        },
+    )
+}
+
+// frame type selected for monitoring, SyncEmergency selects emergencies only
+// as SYNC (node 0) would flood the output
+fn is_monitored_type(frame: &col::CANOpenFrame, frame_types: &[col::FrameType]) -> bool {
+    match frame.frame_type() {
+        col::FrameType::SyncEmergency if frame.node_id() == 0 => false,
+        frame_type => frame_types.contains(&frame_type),
+    }
+}
+
+// aligned frame columns, emergencies followed by their error category
+fn frame_line(frame: &col::CANOpenFrame) -> String {
+    let line = frame.format_aligned();
+    match col::EmergencyPayload::from_frame(frame) {
+        Some(emcy) => format!("{}  [{}]", line, emcy.category()),
+        None => line,
+    }
+}
+
+// seconds with millisecond resolution, right aligned to keep columns
+// up to 999999 s (11 days)
+fn format_seconds(duration: Duration, signed: bool) -> String {
+    if signed {
+        format!("{:>+10.3}", duration.as_secs_f64())
+    } else {
+        format!("{:>10.3}", duration.as_secs_f64())
+    }
+}
+
+// time stamp, interface tag padded to the given width and frame,
+// the whole line cut to at most width characters
+fn monitor_line(
+    timestamp: Option<&str>,
+    interface: Option<(&str, usize)>,
+    frame: &str,
+    width: Option<usize>,
+) -> String {
+    let mut line = String::new();
+    if let Some(timestamp) = timestamp {
+        line.push_str(&format!("[{}] ", timestamp));
+    }
+    if let Some((interface, interface_width)) = interface {
+        line.push_str(&format!("{:<1$} ", interface, interface_width));
+    }
+    line.push_str(frame);
+    line.chars()
+        .take(width.unwrap_or(usize::MAX))
+        .collect::<String>()
+        .trim_end()
+        .to_string()
+}
+
+fn receive_error_frames(interface: &str, socket: &CANSocket) {
+    if let Err(error) = socket.error_filter_accept_all() {
+        error!("Cannot receive error frames on {}: {}", interface, error);
+    }
+}
+
+fn open_socket(interface: &str) -> CANSocket {
+    match CANSocket::open(interface) {
+        Ok(socket) => socket,
+        Err(error) => {
+            error!("Error opening {}: {}", interface, error);
+            quit::with_code(1);
+        }
+    }
+}
+
+// only monitoring listens on several interfaces
+fn check_interfaces(cli: &Cli) -> Result<(), String> {
+    match cli.command {
+        Some(Commands::Mon { .. }) => Ok(()),
+        _ if cli.interface.len() > 1 => Err(format!(
+            "Several interfaces given ({}), only mon accepts more than one",
+            cli.interface.join(", ")
+        )),
+        _ => Ok(()),
+    }
+}
+
 #[quit::main]
 fn main() {
     let cli = Cli::parse();
@@ -389,71 +678,109 @@ fn main() {
         .init();
 
     debug!("Verbose: {:?}", cli.verbose);
-    info!("CAN interface: {}", cli.interface);
+    if let Some(Commands::Abort { code }) = &cli.command {
+        // no bus access required
+        println!("{}", abort_code_description(*code));
+        return;
+    }
+
+    if let Err(error) = check_interfaces(&cli) {
+        error!("{}", error);
+        quit::with_code(1);
+    }
+    info!("CAN interface: {}", cli.interface.join(", "));
 
     let my_future = async {
-        let mut can_socket = match CANSocket::open(&cli.interface) {
-            Ok(socket) => socket,
-            Err(error) => {
-                error!("Error opening {}: {}", cli.interface, error);
-                quit::with_code(1);
-            }
-        };
+        let mut can_socket = open_socket(&cli.interface[0]);
 
         match &cli.command {
             Some(Commands::Rod {
                 node,
-                index,
+                address,
                 subindex,
+                value_type,
+                lenient_subindex,
             }) => {
-                info!("Read Object Directory {}@{},{}", node, index, subindex);
+                let address = match with_subindex(*address, *subindex) {
+                    Ok(address) => address,
+                    Err(error) => {
+                        error!("{}", error);
+                        quit::with_code(1);
+                    }
+                };
+                info!("Read Object Directory {} @ {}", address, node);
                 read_remote_object_with_acknowledge_check(
                     &mut can_socket,
                     *node,
-                    *index,
-                    *subindex,
+                    address,
+                    *value_type,
+                    *lenient_subindex,
                 )
                 .await;
             }
             Some(Commands::Wod {
                 node,
-                index,
-                subindex,
+                address,
                 value_type,
                 value,
             }) => {
+                let value = match parse_value(*value_type, value)
+                    .and_then(|value| u32::try_from(value).map_err(|x| x.to_string()))
+                {
+                    Ok(value) => value,
+                    Err(error) => {
+                        error!("{}", error);
+                        quit::with_code(1);
+                    }
+                };
                 info!(
-                    "Write Communication Object: {}@{},{} -> {}",
-                    node, index, subindex, value
+                    "Write Communication Object: {} @ {} -> {}",
+                    address, node, value
                 );
                 write_remote_object_with_acknowledge_check(
                     &mut can_socket,
                     *node,
-                    *index,
-                    *subindex,
+                    *address,
                     *value_type,
-                    *value,
+                    value,
                 )
                 .await;
             }
             Some(Commands::Pdo {
                 cobid,
                 remote,
+                rpdo_only,
                 value_type,
                 value,
             }) => {
+                let value = match parse_value(*value_type, value) {
+                    Ok(value) => value,
+                    Err(error) => {
+                        error!("{}", error);
+                        quit::with_code(1);
+                    }
+                };
+                if *rpdo_only {
+                    if let Err(error) = col::pdo_cobid_check(*cobid, true) {
+                        error!("{}", error);
+                        quit::with_code(1);
+                    }
+                }
                 info!(
                     "Inject PDO cobid 0x{:x} RFR {} Value: 0x{:x}",
                     cobid, remote, value
                 );
-                send_pdo(&mut can_socket, *cobid, *remote, *value_type, *value).await;
+                send_pdo(&mut can_socket, *cobid, *remote, *value_type, value).await;
             }
             Some(Commands::Mon {
                 nodes,
                 cobids,
                 frame_types,
                 timestamp,
+                timestamp_mode,
+                width,
             }) => {
+                let nodes: Vec<u8> = nodes.iter().map(|node| u8::from(*node)).collect();
                 if !nodes.is_empty() {
                     info!("Monitor traffic for node {:02x}", nodes.as_hex());
                 } else {
@@ -467,6 +794,7 @@ fn main() {
                 let all_frame_types = vec![
                     col::FrameType::NmtErrorControl,
                     col::FrameType::Nmt,
+                    col::FrameType::SyncEmergency,
                     col::FrameType::SsdoRx,
                     col::FrameType::SsdoTx,
                     col::FrameType::Rpdo1,
@@ -479,6 +807,8 @@ fn main() {
                     col::FrameType::Tpdo4,
                 ];
 
+                let show_bus_errors =
+                    frame_types.is_empty() || frame_types.contains(&FrameType::Err);
                 let mut frame_types = frame_types
                     .iter()
                     .flat_map(|x| match *x {
@@ -513,14 +843,14 @@ fn main() {
                             col::FrameType::Nmt,
                         ],
                         FrameType::Emg => [
-                            col::FrameType::NmtErrorControl,
-                            col::FrameType::NmtErrorControl,
-                            col::FrameType::NmtErrorControl,
-                            col::FrameType::NmtErrorControl,
-                            col::FrameType::NmtErrorControl,
-                            col::FrameType::NmtErrorControl,
-                            col::FrameType::NmtErrorControl,
-                            col::FrameType::NmtErrorControl,
+                            col::FrameType::SyncEmergency,
+                            col::FrameType::SyncEmergency,
+                            col::FrameType::SyncEmergency,
+                            col::FrameType::SyncEmergency,
+                            col::FrameType::SyncEmergency,
+                            col::FrameType::SyncEmergency,
+                            col::FrameType::SyncEmergency,
+                            col::FrameType::SyncEmergency,
                         ],
                         FrameType::Err => [
                             col::FrameType::NmtErrorControl,
@@ -538,28 +868,322 @@ fn main() {
                     frame_types = all_frame_types;
                 }
 
+                let timestamp_mode = match (timestamp, timestamp_mode) {
+                    (_, Some(mode)) => Some(*mode),
+                    (true, None) => Some(TimestampMode::Relative),
+                    (false, None) => None,
+                };
+                // pad interface names to align the frame columns
+                let interface_width = match cli.interface.len() {
+                    1 => None,
+                    _ => cli.interface.iter().map(|name| name.chars().count()).max(),
+                };
+                let mut sockets = vec![(cli.interface[0].clone(), can_socket)];
+                for interface in cli.interface.iter().skip(1) {
+                    sockets.push((interface.clone(), open_socket(interface)));
+                }
+                let mut frames = select_all(
+                    sockets
+                        .into_iter()
+                        .map(|(name, socket)| Box::pin(resilient_frames(name, socket))),
+                );
+
                 let start_time = Instant::now();
-                while let Some(Ok(frame)) = can_socket.next().await {
-                    match col::CANOpenFrame::try_from(frame) {
-                        Ok(frame) => {
-                            if frame_types.contains(&frame.frame_type())
-                                && (nodes.is_empty()
-                                    && (cobids.is_empty() || cobids.contains(&frame.cob_id()))
-                                    || nodes.contains(&frame.node_id()))
+                let mut last_time = start_time;
+                while let Some((interface, frame)) = frames.next().await {
+                    let line = match col::error_frame::decode(&frame) {
+                        Some(bus_error) if show_bus_errors => format!("CAN Error: {}", bus_error),
+                        Some(_) => continue,
+                        None => match col::CANOpenFrame::try_from(frame) {
+                            Ok(frame)
+                                if is_monitored_type(&frame, &frame_types)
+                                    && (nodes.is_empty()
+                                        && (cobids.is_empty()
+                                            || cobids.contains(&frame.cob_id()))
+                                        || nodes.contains(&frame.node_id())) =>
                             {
-                                if *timestamp {
-                                    print!("[{:?}] ", start_time.elapsed());
-                                }
-                                println!("{}", frame);
+                                frame_line(&frame)
                             }
+                            Ok(_) => continue,
+                            Err(e) => {
+                                error!("{}", e);
+                                continue;
+                            }
+                        },
+                    };
+                    let now = Instant::now();
+                    let timestamp = timestamp_mode.map(|mode| match mode {
+                        TimestampMode::Relative => {
+                            format_seconds(now.duration_since(start_time), false)
                         }
-                        Err(e) => error!("{}", e),
-                    }
+                        TimestampMode::Absolute => Local::now().format("%H:%M:%S%.3f").to_string(),
+                        TimestampMode::Delta => format_seconds(now.duration_since(last_time), true),
+                    });
+                    last_time = now;
+                    let interface = interface_width.map(|width| (interface.as_str(), width));
+                    println!(
+                        "{}",
+                        monitor_line(timestamp.as_deref(), interface, &line, *width)
+                    );
                 }
             }
-            None => {}
+            Some(Commands::Abort { .. }) | None => {}
         };
     };
     let rt = tokio::runtime::Runtime::new().unwrap();
     rt.block_on(my_future) // tokio async runtime
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_value() {
+        assert!(encode_value(ValueType::None, 0x1234).is_empty());
+        assert_eq!(vec![0x34], encode_value(ValueType::U8, 0x1234));
+        assert_eq!(vec![0x34, 0x12], encode_value(ValueType::U16, 0x1234));
+        assert_eq!(
+            vec![0x78, 0x56, 0x34, 0x12],
+            encode_value(ValueType::U32, 0x1234_5678)
+        );
+        assert_eq!(
+            vec![8, 7, 6, 5, 4, 3, 2, 1],
+            encode_value(ValueType::U64, 0x0102_0304_0506_0708)
+        );
+        assert_eq!(
+            vec![0x56, 0x34, 0x12],
+            encode_value(ValueType::U24, 0x12_3456)
+        );
+        assert_eq!(
+            vec![0xFE, 0xFF, 0xFF],
+            encode_value(ValueType::I24, 0xFF_FFFE)
+        );
+        assert_eq!(
+            vec![5, 4, 3, 2, 1],
+            encode_value(ValueType::U40, 0xFF01_0203_0405)
+        );
+        assert_eq!(7, encode_value(ValueType::I56, 0).len());
+        assert_eq!(vec![1], encode_value(ValueType::Bool, 2));
+    }
+
+    #[test]
+    fn test_pdo_rpdo_only() {
+        assert!(Cli::try_parse_from(["cot", "pdo", "0x185", "u8", "1"]).is_ok());
+        assert!(
+            Cli::try_parse_from(["cot", "pdo", "--rpdo-only", "-r", "0x185", "u8", "1"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_value() {
+        assert_eq!(Ok(1), parse_value(ValueType::Bool, "true"));
+        assert_eq!(Ok(0), parse_value(ValueType::Bool, "false"));
+        assert_eq!(Ok(1), parse_value(ValueType::Bool, "1"));
+        assert!(parse_value(ValueType::Bool, "2").is_err());
+        assert_eq!(Ok(0x1234), parse_value(ValueType::U16, "0x1234"));
+        assert!(parse_value(ValueType::U8, "true").is_err());
+        let cli = Cli::parse_from(["cot", "wod", "5", "0x2000,0x01", "bool", "true"]);
+        match cli.command {
+            Some(Commands::Wod {
+                value_type, value, ..
+            }) => assert_eq!(Ok(1), parse_value(value_type, &value)),
+            _ => panic!("wod expected"),
+        }
+    }
+
+    #[test]
+    fn test_decode_value() {
+        assert_eq!(
+            Data::UNSIGNED24(0x12_3456),
+            decode_value(ValueType::U24, 0xFF12_3456, None).unwrap()
+        );
+        assert_eq!(
+            Data::INTEGER24(-2),
+            decode_value(ValueType::I24, 0x00FF_FFFE, Some(3)).unwrap()
+        );
+        assert!(decode_value(ValueType::U40, 0, None).is_err());
+        assert_eq!(
+            Data::BOOLEAN(true),
+            decode_value(ValueType::Bool, 0x01, Some(1)).unwrap()
+        );
+        // the object is wider than the requested type
+        assert!(decode_value(ValueType::U8, 0x1234_5678, Some(4)).is_err());
+        assert!(decode_value(ValueType::U32, 0x12, Some(1)).is_err());
+    }
+
+    #[test]
+    fn test_expedited_value_type() {
+        assert_eq!(Ok(ValueType::U32), expedited_value_type("u32"));
+        assert_eq!(Ok(ValueType::I24), expedited_value_type("i24"));
+        for value_type in ["u40", "u48", "u56", "u64", "i40", "i48", "i56"] {
+            assert!(expedited_value_type(value_type).is_err());
+        }
+        assert!(Cli::try_parse_from(["cot", "rod", "5", "0x1000", "-t", "u64"]).is_err());
+        assert!(Cli::try_parse_from(["cot", "wod", "5", "0x2000", "u40", "1"]).is_err());
+    }
+
+    #[test]
+    fn test_is_download_response() {
+        let node = col::NodeId::new(0x05).unwrap();
+        let address = ObjectAddress::new(0x2000, 0x01);
+        let ack = |id, index, subindex| {
+            col::successful_download_acknowledgment_frame(
+                col::NodeId::new(id).unwrap(),
+                0x580,
+                index,
+                subindex,
+            )
+            .unwrap()
+        };
+        assert!(is_download_response(
+            &ack(0x05, 0x2000, 0x01),
+            node,
+            address
+        ));
+        assert!(!is_download_response(
+            &ack(0x05, 0x2000, 0x02),
+            node,
+            address
+        ));
+        assert!(!is_download_response(
+            &ack(0x05, 0x1017, 0x00),
+            node,
+            address
+        ));
+        assert!(!is_download_response(
+            &ack(0x06, 0x2000, 0x01),
+            node,
+            address
+        ));
+    }
+
+    #[test]
+    fn test_response_matches() {
+        let frame =
+            col::upload_4_bytes_frame(col::NodeId::new(0x05).unwrap(), 0x580, 0x1018, 0x00, [0; 4])
+                .unwrap();
+        let response = SDOServerResponse::parse(&frame).unwrap();
+        assert!(response_matches(
+            &response,
+            ObjectAddress::new(0x1018, 0x00),
+            false
+        ));
+        assert!(!response_matches(
+            &response,
+            ObjectAddress::new(0x1018, 0x01),
+            false
+        ));
+        assert!(response_matches(
+            &response,
+            ObjectAddress::new(0x1018, 0x01),
+            true
+        ));
+        assert!(!response_matches(
+            &response,
+            ObjectAddress::new(0x1017, 0x00),
+            true
+        ));
+    }
+
+    #[test]
+    fn test_completed_within() {
+        let timeout = Duration::from_millis(10);
+        assert!(futures::executor::block_on(completed_within(
+            async {},
+            timeout
+        )));
+        assert!(!futures::executor::block_on(completed_within(
+            futures::future::pending(),
+            timeout
+        )));
+    }
+
+    #[test]
+    fn test_object_address_argument() {
+        match Cli::parse_from(["cot", "rod", "5", "0x1018,0x01"]).command {
+            Some(Commands::Rod { node, address, .. }) => {
+                assert_eq!(5, u8::from(node));
+                assert_eq!(ObjectAddress::new(0x1018, 0x01), address);
+            }
+            _ => panic!("rod expected"),
+        }
+        match Cli::parse_from(["cot", "rod", "5", "0x1018", "0x01"]).command {
+            Some(Commands::Rod {
+                address, subindex, ..
+            }) => assert_eq!(
+                Ok(ObjectAddress::new(0x1018, 0x01)),
+                with_subindex(address, subindex)
+            ),
+            _ => panic!("rod expected"),
+        }
+        assert!(with_subindex(ObjectAddress::new(0x1018, 0x02), Some(0x01)).is_err());
+        assert!(Cli::try_parse_from(["cot", "wod", "5", "0x2000,0x100", "u8", "1"]).is_err());
+        assert!(Cli::try_parse_from(["cot", "rod", "0x80", "0x1000"]).is_err());
+    }
+
+    #[test]
+    fn test_check_interfaces() {
+        let cli = Cli::parse_from(["cot", "-i", "can0", "-i", "can1", "mon"]);
+        assert!(check_interfaces(&cli).is_ok());
+        let cli = Cli::parse_from(["cot", "-i", "can1", "rod", "5", "0x1000"]);
+        assert!(check_interfaces(&cli).is_ok());
+        let cli = Cli::parse_from(["cot", "-i", "can0", "-i", "can1", "rod", "5", "0x1000"]);
+        assert!(check_interfaces(&cli).is_err());
+    }
+
+    #[test]
+    fn test_is_monitored_type() {
+        // -f emg
+        let emg = [col::FrameType::SyncEmergency];
+        let node = col::NodeId::new(0x05).unwrap();
+        let emcy = col::emergency_frame(node, 0x8130, 0x11, [0; 5]).unwrap();
+        assert!(is_monitored_type(&emcy, &emg));
+        assert!(!is_monitored_type(&col::sync_frame().unwrap(), &emg));
+        assert!(!is_monitored_type(&emcy, &[col::FrameType::Nmt]));
+    }
+
+    #[test]
+    fn test_frame_line() {
+        let node = col::NodeId::new(0x05).unwrap();
+        let emcy = col::emergency_frame(node, 0x8130, 0x11, [0; 5]).unwrap();
+        assert!(frame_line(&emcy).ends_with("  [monitoring]"));
+        let clear = col::emergency_clear_frame(node, 0x00).unwrap();
+        assert!(frame_line(&clear).ends_with("  [error reset or no error]"));
+        let sync = col::sync_frame().unwrap();
+        assert_eq!(sync.format_aligned(), frame_line(&sync));
+    }
+
+    #[test]
+    fn test_monitor_line() {
+        assert_eq!(
+            format_seconds(Duration::from_millis(9999), false).len(),
+            format_seconds(Duration::from_millis(10000), false).len()
+        );
+        assert_eq!(
+            "[    +0.250]",
+            format!("[{}]", format_seconds(Duration::from_millis(250), true))
+        );
+        let short = monitor_line(Some("     9.999"), Some(("can0", 6)), "Nmt", None);
+        let long = monitor_line(Some("    10.000"), Some(("vcan10", 6)), "Nmt", None);
+        assert_eq!("[     9.999] can0   Nmt", short);
+        assert_eq!(short.find("Nmt"), long.find("Nmt"));
+        assert_eq!(
+            "[  10.000] vc",
+            monitor_line(Some("  10.000"), Some(("vcan10", 6)), "Nmt", Some(13))
+        );
+        assert_eq!("Nmt", monitor_line(None, None, "Nmt   ", Some(5)));
+    }
+
+    #[test]
+    fn test_abort_code_description() {
+        assert_eq!(
+            "0x06090011: Sub-index does not exist",
+            abort_code_description(0x0609_0011)
+        );
+        assert_eq!(
+            "0x12345678: Unknown abort code",
+            abort_code_description(0x1234_5678)
+        );
+    }
+}